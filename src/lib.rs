@@ -12,6 +12,10 @@ pub mod tree {
         Right
     }
 
+    // a node that a change to the tree needed was missing
+    #[derive(Debug, PartialEq)]
+    pub struct TreeError;
+
     pub struct TreeNode<T> {
         pub value: T,
         left: Option<NodeIndex>,
@@ -51,6 +55,12 @@ pub mod tree {
         root: Option<NodeIndex>
     }
 
+    impl<T> Default for Tree<T> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
     impl<T> Tree<T> {
         pub fn new() -> Self {
             Tree {
@@ -81,22 +91,20 @@ pub mod tree {
         pub fn add_node(&mut self, node: TreeNode<T>) -> NodeIndex {
             let index_loc = self.index.len();
             self.index.push(Some(node));
-            return index_loc
+            index_loc
         }
 
         pub fn add_node_with_children(&mut self, node: TreeNode<T>, left_child: Option<NodeIndex>, right_child: Option<NodeIndex>) -> NodeIndex {
             let index_loc = self.add_node(node);
-            if left_child.is_some() {
-                if self.set_node_child(index_loc, left_child, ChildSide::Left).is_err() {
-                    // eat this error?
-                }
+            if left_child.is_some() &&
+                    self.set_node_child(index_loc, left_child, ChildSide::Left).is_err() {
+                // eat this error?
             }
-            if right_child.is_some() {
-                if self.set_node_child(index_loc, right_child, ChildSide::Right).is_err() {
-                    // eat this error?
-                }
+            if right_child.is_some() &&
+                    self.set_node_child(index_loc, right_child, ChildSide::Right).is_err() {
+                // eat this error?
             }
-            return index_loc
+            index_loc
         }
     
         pub fn remove_node_at(&mut self, index_loc: NodeIndex) -> Option<TreeNode<T>> {
@@ -112,7 +120,7 @@ pub mod tree {
         }
     
         pub fn node_at(&self, index_loc: NodeIndex) -> Option<&TreeNode<T>> {
-            return if let Some(node) = self.index.get(index_loc) {
+            if let Some(node) = self.index.get(index_loc) {
                 node.as_ref()
             } else {
                 None
@@ -120,7 +128,7 @@ pub mod tree {
         }
     
         pub fn node_at_mut(&mut self, index_loc: NodeIndex) -> Option<&mut TreeNode<T>> {
-            return if let Some(node) = self.index.get_mut(index_loc) {
+            if let Some(node) = self.index.get_mut(index_loc) {
                 node.as_mut()
             } else {
                 None
@@ -128,7 +136,7 @@ pub mod tree {
         }
 
         pub fn set_node_child(&mut self, node_loc: NodeIndex,
-                child_loc: Option<NodeIndex>, child_side: ChildSide,) -> Result<(),()> {
+                child_loc: Option<NodeIndex>, child_side: ChildSide,) -> Result<(),TreeError> {
             
             // if the node's child is already set, unset that node's parent
             //   (may not be needed, since there's no way to traverse to the
@@ -140,18 +148,12 @@ pub mod tree {
                         ChildSide::Right => parent.right
                     }
                 },
-                None => { return Err(()); }
+                None => { return Err(TreeError); }
             };
-            match prev_child_loc {
-                Some(prev_child) => {
-                    match self.node_at_mut(prev_child) {
-                        Some(child) => {
-                            child.parent = None;
-                        },
-                        None => ()
-                    }
-                },
-                None => ()
+            if let Some(prev_child) = prev_child_loc {
+                if let Some(child) = self.node_at_mut(prev_child) {
+                    child.parent = None;
+                }
             }
 
             // set the new child
@@ -163,36 +165,33 @@ pub mod tree {
                     }
                     
                 },
-                None => { return Err(()); }
+                None => { return Err(TreeError); }
             }
 
             // set the new child node's parent
-            match child_loc {
-                Some(child_loc) => {
-                    match self.node_at_mut(child_loc) {
-                        Some(child) => {
-                            child.parent = Some(node_loc);
-                        },
-                        None => { return Err(()); }
-                    }
-                },
-                None => ()
+            if let Some(child_loc) = child_loc {
+                match self.node_at_mut(child_loc) {
+                    Some(child) => {
+                        child.parent = Some(node_loc);
+                    },
+                    None => { return Err(TreeError); }
+                }
             }
-            return Ok(());
+            Ok(())
         }
 
         pub fn get_node_parent(&self, node_loc: NodeIndex) -> Option<NodeIndex> {
             match self.node_at(node_loc) {
-                Some(child) => { return child.parent; },
-                None => { return None; }
-            };
+                Some(child) => child.parent,
+                None => None
+            }
         }
 
         // TODO: don't we need to replace the "from node"'s child with a None?
         //   otherwise the moved child will now have two parents
         pub fn set_node_child_from_node_child(&mut self,
                 set_to_loc: NodeIndex, set_to_side: ChildSide,
-                set_from_loc: NodeIndex, set_from_side: ChildSide) -> Result<(),()> {
+                set_from_loc: NodeIndex, set_from_side: ChildSide) -> Result<(),TreeError> {
             let new_child = match self.node_at(set_from_loc) {
                 Some(f) => {
                     match set_from_side {
@@ -200,7 +199,7 @@ pub mod tree {
                         ChildSide::Right => f.right
                     }
                 },
-                None => { return Err(()); }
+                None => { return Err(TreeError); }
             };
 
             let to_node = self.node_at_mut(set_to_loc);
@@ -214,21 +213,21 @@ pub mod tree {
                             t.right = new_child;
                         }
                     };
-                    return Ok(());
+                    Ok(())
                 } ,
-                None => Err(())
+                None => Err(TreeError)
             }
         }
 
         pub fn replace_root_with_node(&mut self,
-                new_root_loc: NodeIndex, move_old_root_to_side: ChildSide) -> Result<(),()> {
+                new_root_loc: NodeIndex, move_old_root_to_side: ChildSide) -> Result<(),TreeError> {
             let old_root_idx = match self.get_root() {
                 Some(r) => r,
-                None => { return Err(()); }
+                None => { return Err(TreeError); }
             };
             let new_root_node = match self.node_at_mut(new_root_loc) {
                 Some(n) => n,
-                None => { return Err(()); }
+                None => { return Err(TreeError); }
             };
             match move_old_root_to_side {
                 ChildSide::Left => {
@@ -239,15 +238,15 @@ pub mod tree {
                 }
             }
             self.set_root(Some(new_root_loc));
-            return Ok(());
+            Ok(())
         }
 
         pub fn insert_node_below_parent(&mut self,
                 parent_node_loc: NodeIndex, old_child_node_side: ChildSide,
-                new_child_node_loc: NodeIndex, new_child_node_side: ChildSide) -> Result<(),()> {
+                new_child_node_loc: NodeIndex, new_child_node_side: ChildSide) -> Result<(),TreeError> {
             
             if !self.has_node_at(parent_node_loc) || !self.has_node_at(new_child_node_loc) {
-                return Err(());
+                return Err(TreeError);
             }
 
             let old_child_loc = match self.node_at(parent_node_loc) {
@@ -257,30 +256,30 @@ pub mod tree {
                         ChildSide::Right => parent_node.right
                     }   
                 },
-                None => { return Err(()); }
+                None => { return Err(TreeError); }
             };
 
             if self.set_node_child(parent_node_loc, Some(new_child_node_loc), old_child_node_side).is_err() {
-                return Err(());
+                return Err(TreeError);
             }
             if self.set_node_child(new_child_node_loc, old_child_loc, new_child_node_side).is_err() {
-                return Err(());
+                return Err(TreeError);
             }
-            return Ok(());
+            Ok(())
         }
 
         pub fn insert_node_above_node(&mut self,
-                old_child_loc: NodeIndex, new_child_loc: NodeIndex, new_child_side: ChildSide) -> Result<(),()> {
+                old_child_loc: NodeIndex, new_child_loc: NodeIndex, new_child_side: ChildSide) -> Result<(),TreeError> {
             let parent = self.get_node_parent(old_child_loc);
             if parent.is_none() {
                 //return Err("cursor is at a value node which is not the root, but it has no parent");
-                return Err(());
+                return Err(TreeError);
             }
             let parent_loc = parent.unwrap();
             let parent = self.node_at(parent_loc);
             if parent.is_none() {
                 //return Err("cursor is at a value node that has no parent node at indicated location");
-                return Err(());
+                return Err(TreeError);
             }
             let parent = parent.unwrap();
             let parent_left = parent.get_left();
@@ -292,7 +291,7 @@ pub mod tree {
                 ChildSide::Right
             } else {
                 //return Err("cursor is at a value node that is somehow neither its parent's left nor right child");
-                return Err(());
+                return Err(TreeError);
             };
 
             let result = self.insert_node_below_parent(
@@ -300,9 +299,9 @@ pub mod tree {
                 new_child_loc, new_child_side);
             if result.is_err() {
                 //return Err("unable to insert new operator node in place of an existing value node");
-                return Err(());
+                return Err(TreeError);
             }
-            return Ok(());
+            Ok(())
         }
 
     }
@@ -322,21 +321,35 @@ pub mod tree {
         pub fn new(tree: &'a Tree<T>) -> Self {
             if let Some(i) = tree.root {
                 PostOrderIter {
-                    tree: tree,
+                    tree,
                     stack: vec![i],
                     visited: HashSet::new()
                 }
             } else {
                 PostOrderIter {
-                    tree: tree,
+                    tree,
                     stack: vec![],
                     visited: HashSet::new()
                 }
             }
         }
 
-        pub fn next(&mut self) -> Option<NodeIndex> {
+        // the node at index, and everything below it, won't be returned
+        //   (unless it already has been), like the branch of an "if" that
+        //   isn't taken
+        pub fn skip_subtree(&mut self, index: NodeIndex) {
+            self.visited.insert(index);
+        }
+    }
+
+    impl<'a, T> Iterator for PostOrderIter<'a, T> {
+        type Item = NodeIndex;
+
+        fn next(&mut self) -> Option<NodeIndex> {
             while let Some(node_index) = self.stack.pop() {
+                if self.visited.contains(&node_index) {
+                    continue;
+                }
                 if let Some(node) = self.tree.node_at(node_index) {
                     self.stack.push(node_index);
                     let mut pushed_right = false;
@@ -364,7 +377,1631 @@ pub mod tree {
                 }
             }
 
-            return None
+            None
+        }
+    }
+}
+
+// math functions for BigDecimal values that the bigdecimal crate doesn't
+//   provide itself
+// transcendental results are computed with some extra guard digits and then
+//   rounded to PRECISION significant digits, which keeps the working precision
+//   below the 100 digits BigDecimal uses internally for division
+pub mod bigmath {
+    use bigdecimal::BigDecimal;
+    use bigdecimal::{One, Signed, ToPrimitive, Zero};
+    use num_bigint::BigInt;
+    use num_integer::Integer;
+    use std::str::FromStr;
+    use crate::rational::Rational;
+
+    pub const PRECISION: u64 = 50;
+    const GUARD_DIGITS: u64 = 10;
+
+    // refuse to compute integer powers whose exact result would have more
+    //   digits than this, since that could hang the UI
+    const MAX_POWER_DIGITS: u64 = 100_000;
+
+    // exp() of anything larger than this has more than ~100k digits
+    const MAX_EXP_ARGUMENT: i64 = 230_000;
+
+    // trig arguments are reduced modulo 2pi, which needs pi to at least as
+    //   many digits as the integer part of the argument
+    const MAX_TRIG_ARGUMENT_DIGITS: i64 = 30;
+
+    const MAX_ROUND_DIGITS: i64 = 10_000;
+
+    // shifting left by this many bits gives roughly MAX_POWER_DIGITS digits
+    const MAX_SHIFT_BITS: u64 = 332_000;
+
+    // 25000! has roughly MAX_POWER_DIGITS digits
+    const MAX_FACTORIAL_ARGUMENT: u64 = 25_000;
+
+    // isprime(), modpow() and modinv() slow down quickly past this many digits
+    //   -- a prime this long takes isprime() around 10ms, and one of 1000
+    //   digits takes half a second
+    const MAX_NUMBER_THEORY_DIGITS: usize = 300;
+
+    // factor() finds small factors by trial division and larger ones with
+    //   Pollard's rho, which gives up after this many steps
+    const MAX_FACTOR_DIGITS: usize = 100;
+    const MAX_TRIAL_DIVISOR: u32 = 10_000;
+    const MAX_RHO_STEPS: u32 = 200_000;
+
+    // Miller-Rabin with these bases is certain below 3.3e24, and otherwise
+    //   wrong for fewer than 1 in 4^20 composites
+    const PRIME_TEST_BASES: [u32; 20] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71];
+
+    // near(a, b) without a tolerance is true when a and b agree to this many
+    //   digits, which is fewer than PRECISION so that results rounded by
+    //   functions like sin() still compare as equal
+    const NEAR_DIGITS: u64 = 40;
+
+    // significant digits kept by a division that doesn't terminate, like 1/3,
+    //   which is as many as BigDecimal's own division keeps
+    pub const DIVISION_DIGITS: u64 = 100;
+
+    // how the digits dropped when rounding affect the digits that are kept
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub enum RoundingMode {
+        // to the nearest, and halfway goes to the even digit ("banker's rounding")
+        HalfEven,
+        // to the nearest, and halfway goes away from zero
+        HalfUp,
+        // toward zero
+        Down,
+        // away from zero
+        Up
+    }
+
+    // the unit that trig functions take angles in, and give them in
+    #[derive(Clone, Copy, PartialEq, Debug)]
+    pub enum AngleUnit {
+        Radians,
+        Degrees,
+        // a right angle is 100 gradians
+        Gradians
+    }
+
+    // BigDecimal::with_prec() and digits() miscount the digits of negative
+    //   values, so round the absolute value instead
+    trait WithPrecision {
+        fn with_precision(&self, prec: u64) -> BigDecimal;
+    }
+
+    impl WithPrecision for BigDecimal {
+        fn with_precision(&self, prec: u64) -> BigDecimal {
+            if self.is_negative() {
+                return -self.abs().with_prec(prec);
+            }
+            self.with_prec(prec)
+        }
+    }
+
+    fn epsilon(prec: u64) -> BigDecimal {
+        BigDecimal::new(1.into(), prec as i64)
+    }
+
+    fn finish(value: BigDecimal) -> BigDecimal {
+        value.with_precision(PRECISION).normalized()
+    }
+
+    fn half() -> BigDecimal {
+        BigDecimal::from_str("0.5").unwrap()
+    }
+
+    fn ten_to_the(power: u32) -> BigInt {
+        BigInt::from(10).pow(power)
+    }
+
+    // number of digits to the left of the decimal point (negative for values
+    //   like 0.001 which have leading zeros after the decimal point)
+    fn integer_digits(x: &BigDecimal) -> i64 {
+        x.abs().digits() as i64 - x.as_bigint_and_exponent().1
+    }
+
+    pub fn abs(x: &BigDecimal) -> Result<BigDecimal, String> {
+        Ok(x.abs())
+    }
+
+    pub fn sign(x: &BigDecimal) -> Result<BigDecimal, String> {
+        if x.is_zero() {
+            return Ok(BigDecimal::zero());
+        }
+        if x.is_negative() {
+            return Ok(BigDecimal::from(-1));
+        }
+        Ok(BigDecimal::one())
+    }
+
+    pub fn floor(x: &BigDecimal) -> Result<BigDecimal, String> {
+        let (digits, scale) = x.as_bigint_and_exponent();
+        if scale <= 0 {
+            return Ok(x.clone());
+        }
+        Ok(BigDecimal::new(digits.div_floor(&ten_to_the(scale as u32)), 0))
+    }
+
+    pub fn ceil(x: &BigDecimal) -> Result<BigDecimal, String> {
+        match floor(&-x) {
+            Ok(f) => Ok(-f),
+            Err(m) => Err(m)
+        }
+    }
+
+    pub fn trunc(x: &BigDecimal) -> Result<BigDecimal, String> {
+        if x.is_negative() {
+            return ceil(x);
+        }
+        floor(x)
+    }
+
+    // rounds halves away from zero, like f64::round()
+    pub fn round(x: &BigDecimal) -> Result<BigDecimal, String> {
+        let rounded_abs = match floor(&(x.abs() + half())) {
+            Ok(f) => f,
+            Err(m) => { return Err(m); }
+        };
+        if x.is_negative() {
+            return Ok(-rounded_abs);
+        }
+        Ok(rounded_abs)
+    }
+
+    // the quotient of a division that has been cut short, adjusted for the
+    //   remainder that was dropped -- the quotient and remainder are both
+    //   truncated toward zero, as BigInt's div_rem() gives them
+    fn round_quotient(quotient: BigInt, remainder: &BigInt, divisor: &BigInt, mode: RoundingMode) -> BigInt {
+        let twice_remainder = remainder.abs() * 2;
+        let divisor = divisor.abs();
+        let is_away_from_zero = match mode {
+            RoundingMode::HalfEven => twice_remainder > divisor || (twice_remainder == divisor && quotient.is_odd()),
+            RoundingMode::HalfUp => twice_remainder >= divisor,
+            RoundingMode::Down => false,
+            RoundingMode::Up => !remainder.is_zero()
+        };
+        if !is_away_from_zero {
+            return quotient;
+        }
+        // the remainder has the sign of the exact quotient, even when the
+        //   truncated quotient is zero
+        if remainder.is_negative() {
+            return quotient - 1;
+        }
+        quotient + 1
+    }
+
+    // places can be negative, to round to the left of the decimal point
+    pub fn round_to_places(x: &BigDecimal, places: i64, mode: RoundingMode) -> BigDecimal {
+        let (digits, scale) = x.as_bigint_and_exponent();
+        if scale <= places {
+            return x.clone();
+        }
+        let divisor = ten_to_the((scale - places) as u32);
+        let (quotient, remainder) = digits.div_rem(&divisor);
+        BigDecimal::new(round_quotient(quotient, &remainder, &divisor, mode), places)
+    }
+
+    pub fn round_to_significant_digits(x: &BigDecimal, digits: u64, mode: RoundingMode) -> BigDecimal {
+        if x.is_zero() {
+            return x.clone();
+        }
+        round_to_places(x, digits as i64 - integer_digits(x), mode)
+    }
+
+    // a division that terminates is exact, and one that doesn't is rounded to
+    //   DIVISION_DIGITS significant digits
+    pub fn divide(a: &BigDecimal, b: &BigDecimal, mode: RoundingMode) -> Result<BigDecimal, String> {
+        if b.is_zero() {
+            return Err("cannot divide by zero".to_string());
+        }
+        if a.is_zero() {
+            return Ok(BigDecimal::zero());
+        }
+        let (a_digits, a_scale) = a.as_bigint_and_exponent();
+        let (b_digits, b_scale) = b.as_bigint_and_exponent();
+        // the quotient has this many digits left of the decimal point, or
+        //   one fewer
+        let quotient_integer_digits = integer_digits(a) - integer_digits(b) + 1;
+        let mut places = DIVISION_DIGITS as i64 - quotient_integer_digits;
+        // a/b shifted left by places is a_digits * 10^shift / b_digits
+        let shift = places + b_scale - a_scale;
+        let (mut numerator, denominator) = if shift >= 0 {
+            (a_digits * ten_to_the(shift as u32), b_digits)
+        } else {
+            (a_digits, b_digits * ten_to_the((-shift) as u32))
+        };
+        let (mut quotient, mut remainder) = numerator.div_rem(&denominator);
+        if quotient.abs().to_string().len() < DIVISION_DIGITS as usize {
+            places += 1;
+            numerator *= 10;
+            let (q, r) = numerator.div_rem(&denominator);
+            quotient = q;
+            remainder = r;
+        }
+        if remainder.is_zero() {
+            return Ok(a / b);
+        }
+        Ok(BigDecimal::new(round_quotient(quotient, &remainder, &denominator, mode), places))
+    }
+
+    pub fn sqrt(x: &BigDecimal) -> Result<BigDecimal, String> {
+        match x.sqrt() {
+            Some(r) => Ok(finish(r)),
+            None => Err(format!("square root of negative value [{}] is undefined", x))
+        }
+    }
+
+    pub fn cbrt(x: &BigDecimal) -> Result<BigDecimal, String> {
+        Ok(finish(x.cbrt()))
+    }
+
+    pub fn log10(x: &BigDecimal) -> Result<BigDecimal, String> {
+        log_base(x, 10)
+    }
+
+    pub fn log2(x: &BigDecimal) -> Result<BigDecimal, String> {
+        log_base(x, 2)
+    }
+
+    fn log_base(x: &BigDecimal, base: u32) -> Result<BigDecimal, String> {
+        let wp = PRECISION + GUARD_DIGITS;
+        let log = match ln_with_prec(x, wp) {
+            Ok(l) => l,
+            Err(m) => { return Err(m); }
+        };
+        let log_base = match ln_with_prec(&BigDecimal::from(base), wp) {
+            Ok(l) => l,
+            Err(m) => { return Err(m); }
+        };
+        Ok(finish(log / log_base))
+    }
+
+    pub fn pi() -> BigDecimal {
+        finish(pi_with_prec(PRECISION + GUARD_DIGITS))
+    }
+
+    // Machin's formula: pi = 16 atan(1/5) - 4 atan(1/239)
+    fn pi_with_prec(prec: u64) -> BigDecimal {
+        let wp = prec + GUARD_DIGITS;
+        let result = atan_inverse_integer(5, wp) * BigDecimal::from(16)
+            - atan_inverse_integer(239, wp) * BigDecimal::from(4);
+        result.with_precision(prec)
+    }
+
+    // atan(1/n) = 1/n - 1/(3n^3) + 1/(5n^5) - ...
+    fn atan_inverse_integer(n: u32, wp: u64) -> BigDecimal {
+        let n_squared = BigDecimal::from(n * n);
+        let eps = epsilon(wp + 2);
+        let mut power = (BigDecimal::one() / BigDecimal::from(n)).with_precision(wp);
+        let mut sum = power.clone();
+        let mut k: u32 = 1;
+        loop {
+            power = (power / &n_squared).with_precision(wp);
+            k += 2;
+            let term = (&power / BigDecimal::from(k)).with_precision(wp);
+            if term < eps {
+                break;
+            }
+            if k % 4 == 3 {
+                sum -= term;
+            } else {
+                sum += term;
+            }
+        }
+        sum
+    }
+
+    // reduce x into [-pi, pi], returning the working precision that should
+    //   be used for the rest of the calculation
+    fn reduce_angle(x: &BigDecimal, prec: u64) -> Result<(BigDecimal, u64), String> {
+        let x_digits = integer_digits(x);
+        if x_digits > MAX_TRIG_ARGUMENT_DIGITS {
+            return Err(format!("argument [{}] is too large for a trigonometric function", x));
+        }
+        let wp = prec + GUARD_DIGITS + std::cmp::max(x_digits, 0) as u64;
+        let pi = pi_with_prec(wp);
+        if x.abs() <= pi {
+            return Ok((x.with_precision(wp), wp));
+        }
+        let two_pi = pi.double();
+        let turns = (x / &two_pi).with_scale(0);
+        let mut reduced = (x - turns * &two_pi).with_precision(wp);
+        if reduced > pi {
+            reduced -= &two_pi;
+        } else if reduced < -&pi {
+            reduced += &two_pi;
+        }
+        Ok((reduced, wp))
+    }
+
+    // sum of (-1)^k x^(2k+start) / (2k+start)! which is cos(x) for start 0
+    //   and sin(x) for start 1
+    fn sin_cos_series(x: &BigDecimal, start: u32, wp: u64) -> BigDecimal {
+        let x_squared = x.square().with_precision(wp);
+        let eps = epsilon(wp + 2);
+        let mut term = if start == 0 { BigDecimal::one() } else { x.clone() };
+        let mut sum = term.clone();
+        let mut n = start;
+        loop {
+            term = (-(&term * &x_squared) / BigDecimal::from((n + 1) * (n + 2))).with_precision(wp);
+            if term.abs() < eps {
+                break;
+            }
+            sum += &term;
+            n += 2;
+        }
+        sum
+    }
+
+    pub fn sin(x: &BigDecimal) -> Result<BigDecimal, String> {
+        match reduce_angle(x, PRECISION + GUARD_DIGITS) {
+            Ok((reduced, wp)) => Ok(finish(sin_cos_series(&reduced, 1, wp))),
+            Err(m) => Err(m)
+        }
+    }
+
+    pub fn cos(x: &BigDecimal) -> Result<BigDecimal, String> {
+        match reduce_angle(x, PRECISION + GUARD_DIGITS) {
+            Ok((reduced, wp)) => Ok(finish(sin_cos_series(&reduced, 0, wp))),
+            Err(m) => Err(m)
+        }
+    }
+
+    pub fn tan(x: &BigDecimal) -> Result<BigDecimal, String> {
+        let (reduced, wp) = match reduce_angle(x, PRECISION + GUARD_DIGITS) {
+            Ok(r) => r,
+            Err(m) => { return Err(m); }
+        };
+        let cos = sin_cos_series(&reduced, 0, wp);
+        if cos.abs() < epsilon(PRECISION) {
+            return Err(format!("tangent of [{}] is undefined", x));
+        }
+        Ok(finish(sin_cos_series(&reduced, 1, wp) / cos))
+    }
+
+    fn atan_with_prec(x: &BigDecimal, prec: u64) -> BigDecimal {
+        let wp = prec + GUARD_DIGITS;
+        if x.is_negative() {
+            return -atan_with_prec(&-x, prec);
+        }
+        if x > &BigDecimal::one() {
+            // atan(x) = pi/2 - atan(1/x)
+            let inverse = (BigDecimal::one() / x).with_precision(wp);
+            return (pi_with_prec(wp).half() - atan_with_prec(&inverse, wp)).with_precision(prec);
+        }
+        // halve the angle until the argument is small, using
+        //   atan(x) = 2 atan(x / (1 + sqrt(1 + x^2)))
+        let small = BigDecimal::from_str("0.1").unwrap();
+        let mut reduced = x.with_precision(wp);
+        let mut halvings: u32 = 0;
+        while reduced > small {
+            let root = (BigDecimal::one() + reduced.square()).sqrt().unwrap();
+            reduced = (&reduced / (BigDecimal::one() + root)).with_precision(wp);
+            halvings += 1;
+        }
+        // atan(x) = x - x^3/3 + x^5/5 - ...
+        let x_squared = reduced.square().with_precision(wp);
+        let eps = epsilon(wp + 2);
+        let mut power = reduced.clone();
+        let mut sum = reduced;
+        let mut k: u32 = 1;
+        loop {
+            power = -(&power * &x_squared).with_precision(wp);
+            k += 2;
+            let term = (&power / BigDecimal::from(k)).with_precision(wp);
+            if term.abs() < eps {
+                break;
+            }
+            sum += term;
+        }
+        for _ in 0..halvings {
+            sum = sum.double();
+        }
+        sum.with_precision(prec)
+    }
+
+    pub fn atan(x: &BigDecimal) -> Result<BigDecimal, String> {
+        atan_in(x, AngleUnit::Radians)
+    }
+
+    pub fn atan_in(x: &BigDecimal, unit: AngleUnit) -> Result<BigDecimal, String> {
+        let wp = PRECISION + GUARD_DIGITS;
+        Ok(finish_angle(atan_with_prec(x, wp), unit, wp))
+    }
+
+    fn asin_with_prec(x: &BigDecimal, prec: u64) -> Result<BigDecimal, String> {
+        let wp = prec + GUARD_DIGITS;
+        if x.abs() > BigDecimal::one() {
+            return Err(format!("arcsine of [{}] is undefined", x));
+        }
+        if x.abs() == BigDecimal::one() {
+            let half_pi = pi_with_prec(wp).half();
+            return Ok(if x.is_negative() { -half_pi } else { half_pi });
+        }
+        // asin(x) = atan(x / sqrt(1 - x^2))
+        let root = (BigDecimal::one() - x.square()).sqrt().unwrap();
+        Ok(atan_with_prec(&(x / root).with_precision(wp), prec))
+    }
+
+    pub fn asin(x: &BigDecimal) -> Result<BigDecimal, String> {
+        asin_in(x, AngleUnit::Radians)
+    }
+
+    pub fn asin_in(x: &BigDecimal, unit: AngleUnit) -> Result<BigDecimal, String> {
+        let wp = PRECISION + GUARD_DIGITS;
+        match asin_with_prec(x, wp) {
+            Ok(r) => Ok(finish_angle(r, unit, wp)),
+            Err(m) => Err(m)
+        }
+    }
+
+    pub fn acos(x: &BigDecimal) -> Result<BigDecimal, String> {
+        acos_in(x, AngleUnit::Radians)
+    }
+
+    pub fn acos_in(x: &BigDecimal, unit: AngleUnit) -> Result<BigDecimal, String> {
+        let wp = PRECISION + GUARD_DIGITS;
+        if x.abs() > BigDecimal::one() {
+            return Err(format!("arccosine of [{}] is undefined", x));
+        }
+        match asin_with_prec(x, wp) {
+            Ok(r) => Ok(finish_angle(pi_with_prec(wp).half() - r, unit, wp)),
+            Err(m) => Err(m)
+        }
+    }
+
+    // half a turn is pi radians, 180 degrees, or 200 gradians
+    fn half_turn(unit: AngleUnit, wp: u64) -> BigDecimal {
+        match unit {
+            AngleUnit::Radians => pi_with_prec(wp),
+            AngleUnit::Degrees => BigDecimal::from(180),
+            AngleUnit::Gradians => BigDecimal::from(200)
+        }
+    }
+
+    fn convert_angle_with_prec(x: &BigDecimal, from: AngleUnit, to: AngleUnit, wp: u64) -> BigDecimal {
+        if from == to {
+            return x.clone();
+        }
+        (x * half_turn(to, wp) / half_turn(from, wp)).with_precision(wp)
+    }
+
+    pub fn convert_angle(x: &BigDecimal, from: AngleUnit, to: AngleUnit) -> BigDecimal {
+        if from == to {
+            return x.clone();
+        }
+        finish(convert_angle_with_prec(x, from, to, PRECISION + GUARD_DIGITS))
+    }
+
+    // the size of one unit keeps its guard digits, since it's usually
+    //   multiplied into a trig argument, so sin(30deg) is exactly 0.5
+    pub fn angle_unit_size(unit: AngleUnit, mode: AngleUnit) -> BigDecimal {
+        convert_angle_with_prec(&BigDecimal::from(1), unit, mode, PRECISION + GUARD_DIGITS).normalized()
+    }
+
+    // an inverse trig result is converted from radians before it's rounded,
+    //   so asin(1) is exactly 90 in degrees
+    fn finish_angle(radians: BigDecimal, unit: AngleUnit, wp: u64) -> BigDecimal {
+        finish(convert_angle_with_prec(&radians, AngleUnit::Radians, unit, wp))
+    }
+
+    // degrees(x) is x radians in degrees, and radians(x) is x degrees in radians
+    pub fn degrees(x: &BigDecimal) -> Result<BigDecimal, String> {
+        Ok(convert_angle(x, AngleUnit::Radians, AngleUnit::Degrees))
+    }
+
+    pub fn radians(x: &BigDecimal) -> Result<BigDecimal, String> {
+        Ok(convert_angle(x, AngleUnit::Degrees, AngleUnit::Radians))
+    }
+
+    // an angle in degrees or gradians is reduced to less than a turn exactly,
+    //   and whole quarter turns have exact results, given here from 0 to 3
+    //   quarter turns (None where the function is undefined) -- so cos(90)
+    //   in degrees is 0, rather than a rounding error like 1e-61
+    fn trig_in(x: &BigDecimal, unit: AngleUnit, function: fn(&BigDecimal) -> Result<BigDecimal, String>,
+            quarter_turn_values: [Option<i32>; 4], name: &str) -> Result<BigDecimal, String> {
+        if unit == AngleUnit::Radians {
+            return function(x);
+        }
+        let quarter_turn = half_turn(unit, 0).half();
+        let reduced = match floor_mod(x, &half_turn(unit, 0).double()) {
+            Ok(r) => r,
+            Err(m) => { return Err(m); }
+        };
+        let is_quarter_turns = match floor_mod(&reduced, &quarter_turn) {
+            Ok(r) => r.is_zero(),
+            Err(m) => { return Err(m); }
+        };
+        if is_quarter_turns {
+            let quarters = match floor_div(&reduced, &quarter_turn) {
+                Ok(q) => q.to_usize().unwrap_or(0),
+                Err(m) => { return Err(m); }
+            };
+            return match quarter_turn_values[quarters % 4] {
+                Some(v) => Ok(BigDecimal::from(v)),
+                None => Err(format!("{} of [{}] is undefined", name, x))
+            };
+        }
+        function(&convert_angle_with_prec(&reduced, unit, AngleUnit::Radians, PRECISION + GUARD_DIGITS))
+    }
+
+    pub fn sin_in(x: &BigDecimal, unit: AngleUnit) -> Result<BigDecimal, String> {
+        trig_in(x, unit, sin, [Some(0), Some(1), Some(0), Some(-1)], "sine")
+    }
+
+    pub fn cos_in(x: &BigDecimal, unit: AngleUnit) -> Result<BigDecimal, String> {
+        trig_in(x, unit, cos, [Some(1), Some(0), Some(-1), Some(0)], "cosine")
+    }
+
+    pub fn tan_in(x: &BigDecimal, unit: AngleUnit) -> Result<BigDecimal, String> {
+        trig_in(x, unit, tan, [Some(0), None, Some(0), None], "tangent")
+    }
+
+    // returns (e^x, e^-x) for the hyperbolic functions
+    fn exp_pair(x: &BigDecimal) -> Result<(BigDecimal, BigDecimal), String> {
+        let wp = PRECISION + GUARD_DIGITS;
+        let positive = match exp_with_prec(x, wp) {
+            Ok(e) => e,
+            Err(m) => { return Err(m); }
+        };
+        let negative = (BigDecimal::one() / &positive).with_precision(wp);
+        Ok((positive, negative))
+    }
+
+    pub fn sinh(x: &BigDecimal) -> Result<BigDecimal, String> {
+        match exp_pair(x) {
+            Ok((p, n)) => Ok(finish((p - n).half())),
+            Err(m) => Err(m)
+        }
+    }
+
+    pub fn cosh(x: &BigDecimal) -> Result<BigDecimal, String> {
+        match exp_pair(x) {
+            Ok((p, n)) => Ok(finish((p + n).half())),
+            Err(m) => Err(m)
+        }
+    }
+
+    pub fn tanh(x: &BigDecimal) -> Result<BigDecimal, String> {
+        match exp_pair(x) {
+            Ok((p, n)) => Ok(finish((&p - &n) / (p + n))),
+            Err(m) => Err(m)
+        }
+    }
+
+    // the functions below take a list of arguments, which the caller has
+    //   already checked has an allowed length
+
+    pub fn min(args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        match args.iter().min() {
+            Some(m) => Ok(m.clone()),
+            None => Err("min() needs at least one argument".to_string())
+        }
+    }
+
+    pub fn max(args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        match args.iter().max() {
+            Some(m) => Ok(m.clone()),
+            None => Err("max() needs at least one argument".to_string())
+        }
+    }
+
+    pub fn sum(args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        let mut total = BigDecimal::zero();
+        for arg in args.iter() {
+            total += arg;
+        }
+        Ok(total)
+    }
+
+    pub fn avg(args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        if args.is_empty() {
+            return Err("avg() needs at least one argument".to_string());
+        }
+        match sum(args) {
+            Ok(total) => Ok(total / BigDecimal::from(args.len() as u64)),
+            Err(m) => Err(m)
+        }
+    }
+
+    // round(x, n) rounds x to n digits after the decimal point (or to tens,
+    //   hundreds, etc. for negative n), with halves rounded away from zero
+    pub fn round_digits(args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        let digits = match args[1].to_i64() {
+            Some(d) if args[1].is_integer() && d.abs() <= MAX_ROUND_DIGITS => d,
+            _ => { return Err(format!("number of digits to round to [{}] must be an integer between -{} and {}", args[1], MAX_ROUND_DIGITS, MAX_ROUND_DIGITS)); }
+        };
+        let shifted = &args[0] * BigDecimal::new(1.into(), -digits);
+        match round(&shifted) {
+            Ok(r) => Ok(r * BigDecimal::new(1.into(), digits)),
+            Err(m) => Err(m)
+        }
+    }
+
+    // atan2(y, x) is the angle of the point (x, y) from the positive x axis
+    fn atan2_with_prec(y: &BigDecimal, x: &BigDecimal, wp: u64) -> BigDecimal {
+        if x.is_zero() {
+            if y.is_zero() {
+                return BigDecimal::zero();
+            }
+            let half_pi = pi_with_prec(wp).half();
+            return if y.is_negative() { -half_pi } else { half_pi };
+        }
+        let angle = atan_with_prec(&(y / x).with_precision(wp), wp);
+        if x.is_positive() {
+            return angle;
+        }
+        if y.is_negative() {
+            return angle - pi_with_prec(wp);
+        }
+        angle + pi_with_prec(wp)
+    }
+
+    pub fn atan2(args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        atan2_in(args, AngleUnit::Radians)
+    }
+
+    pub fn atan2_in(args: &[BigDecimal], unit: AngleUnit) -> Result<BigDecimal, String> {
+        let wp = PRECISION + GUARD_DIGITS;
+        Ok(finish_angle(atan2_with_prec(&args[0], &args[1], wp), unit, wp))
+    }
+
+    // the simplest fraction within a tolerance of a value
+    pub fn approx(args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        let x = Rational::from_bigdecimal(&args[0]);
+        let tolerance = Rational::from_bigdecimal(&args[1]);
+        match x.approximate(&tolerance) {
+            Ok(a) => Ok(a.to_bigdecimal(RoundingMode::HalfEven)),
+            Err(m) => Err(m)
+        }
+    }
+
+    // 1 if a and b are within a tolerance of each other, and 0 otherwise --
+    //   without one, the tolerance grows with the larger of them, so that
+    //   near(sin(pi), 0) and near(exp(100), exp(50)^2) are both 1
+    pub fn near(args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        let difference = (&args[0] - &args[1]).abs();
+        let tolerance = match args.get(2) {
+            Some(t) if t.is_negative() => { return Err(format!("the tolerance [{}] of near() cannot be negative", t)); },
+            Some(t) => t.clone(),
+            None => {
+                let largest = std::cmp::max(std::cmp::max(args[0].abs(), args[1].abs()), BigDecimal::one());
+                largest * epsilon(NEAR_DIGITS)
+            }
+        };
+        if difference <= tolerance {
+            return Ok(BigDecimal::one());
+        }
+        Ok(BigDecimal::zero())
+    }
+
+    pub fn hypot(args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        sqrt(&(args[0].square() + args[1].square()))
+    }
+
+    // scale both values up to integers with a common scale, so integer
+    //   division can be done exactly
+    fn to_common_scale(a: &BigDecimal, b: &BigDecimal) -> (BigInt, BigInt, i64) {
+        let scale = std::cmp::max(a.as_bigint_and_exponent().1, b.as_bigint_and_exponent().1);
+        let (a_int, _) = a.with_scale(scale).into_bigint_and_exponent();
+        let (b_int, _) = b.with_scale(scale).into_bigint_and_exponent();
+        (a_int, b_int, scale)
+    }
+
+    // floor_div() and floor_mod() use the floored convention: the quotient is
+    //   rounded toward negative infinity, and the remainder takes the sign of
+    //   the divisor, so that a == b * (a // b) + a % b always holds
+    pub fn floor_div(a: &BigDecimal, b: &BigDecimal) -> Result<BigDecimal, String> {
+        if b.is_zero() {
+            return Err("cannot divide by zero".to_string());
+        }
+        let (a_int, b_int, _) = to_common_scale(a, b);
+        Ok(BigDecimal::new(a_int.div_floor(&b_int), 0))
+    }
+
+    pub fn floor_mod(a: &BigDecimal, b: &BigDecimal) -> Result<BigDecimal, String> {
+        if b.is_zero() {
+            return Err("cannot divide by zero".to_string());
+        }
+        let (a_int, b_int, scale) = to_common_scale(a, b);
+        Ok(BigDecimal::new(a_int.mod_floor(&b_int), scale))
+    }
+
+    fn to_integer(x: &BigDecimal, op: &str) -> Result<BigInt, String> {
+        if !x.is_integer() {
+            return Err(format!("[{}] only works on integers, not [{}]", op, x));
+        }
+        let (digits, scale) = x.with_scale(0).as_bigint_and_exponent();
+        if scale != 0 {
+            return Err(format!("unable to convert [{}] to an integer", x));
+        }
+        Ok(digits)
+    }
+
+    // a count of things, for factorial(), ncr() and npr()
+    fn to_count(x: &BigDecimal, op: &str) -> Result<BigInt, String> {
+        let x_int = match to_integer(x, op) {
+            Ok(x) => x,
+            Err(_) => { return Err(format!("[{}] only works on whole numbers, not [{}] (there's no gamma function)", op, x)); }
+        };
+        if x_int.is_negative() {
+            return Err(format!("[{}] only works on whole numbers, not [{}]", op, x));
+        }
+        Ok(x_int)
+    }
+
+    // n * (n - 1) * ... * (n - k + 1), refusing results with too many digits
+    fn falling_factorial(n: &BigInt, k: &BigInt, op: &str) -> Result<BigInt, String> {
+        let too_large = format!("[{}] of [{}] and [{}] is too large to calculate", op, n, k);
+        let k = match k.to_u64() {
+            Some(k) if k <= MAX_FACTORIAL_ARGUMENT => k,
+            _ => { return Err(too_large); }
+        };
+        if k * n.to_string().len() as u64 > MAX_POWER_DIGITS {
+            return Err(too_large);
+        }
+        let mut product = BigInt::one();
+        let mut factor = n.clone();
+        for _ in 0..k {
+            product *= &factor;
+            factor -= 1;
+        }
+        Ok(product)
+    }
+
+    pub fn factorial(x: &BigDecimal) -> Result<BigDecimal, String> {
+        let n = match to_count(x, "!") {
+            Ok(n) => n,
+            Err(m) => { return Err(m); }
+        };
+        if n > BigInt::from(MAX_FACTORIAL_ARGUMENT) {
+            return Err(format!("[{}!] is too large to calculate (the limit is {}!)", n, MAX_FACTORIAL_ARGUMENT));
+        }
+        let mut product = BigInt::one();
+        let mut factor = BigInt::from(2);
+        while factor <= n {
+            product *= &factor;
+            factor += 1;
+        }
+        Ok(BigDecimal::from(product))
+    }
+
+    // the number of ways to choose k of n things, ignoring their order
+    pub fn ncr(args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        let (n, k) = match (to_count(&args[0], "nCr"), to_count(&args[1], "nCr")) {
+            (Ok(n), Ok(k)) => (n, k),
+            (Err(m), _) | (_, Err(m)) => { return Err(m); }
+        };
+        if k > n {
+            return Ok(BigDecimal::zero());
+        }
+        // nCr(n, k) is nCr(n, n - k), and the smaller one is quicker
+        let k = std::cmp::min(k.clone(), &n - &k);
+        let numer = match falling_factorial(&n, &k, "nCr") {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        // k! is the falling factorial of k things taken k at a time
+        let denom = match falling_factorial(&k, &k, "nCr") {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        Ok(BigDecimal::from(numer / denom))
+    }
+
+    // the number of ways to arrange k of n things, in order
+    pub fn npr(args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        let (n, k) = match (to_count(&args[0], "nPr"), to_count(&args[1], "nPr")) {
+            (Ok(n), Ok(k)) => (n, k),
+            (Err(m), _) | (_, Err(m)) => { return Err(m); }
+        };
+        if k > n {
+            return Ok(BigDecimal::zero());
+        }
+        match falling_factorial(&n, &k, "nPr") {
+            Ok(x) => Ok(BigDecimal::from(x)),
+            Err(m) => Err(m)
+        }
+    }
+
+    fn to_number_theory_integer(x: &BigDecimal, op: &str) -> Result<BigInt, String> {
+        let x_int = match to_integer(x, op) {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        if x_int.abs().to_string().len() > MAX_NUMBER_THEORY_DIGITS {
+            return Err(format!("[{}] only works on integers of up to {} digits", op, MAX_NUMBER_THEORY_DIGITS));
+        }
+        Ok(x_int)
+    }
+
+    fn to_modulus(m: &BigDecimal, op: &str) -> Result<BigInt, String> {
+        let m_int = match to_number_theory_integer(m, op) {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        if !m_int.is_positive() {
+            return Err(format!("the modulus of [{}] must be positive, not [{}]", op, m_int));
+        }
+        Ok(m_int)
+    }
+
+    pub fn gcd(args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        let mut result = BigInt::zero();
+        for arg in args.iter() {
+            match to_integer(arg, "gcd") {
+                Ok(x) => { result = result.gcd(&x); },
+                Err(m) => { return Err(m); }
+            }
+        }
+        Ok(BigDecimal::from(result))
+    }
+
+    pub fn lcm(args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        let mut result = BigInt::one();
+        for arg in args.iter() {
+            match to_integer(arg, "lcm") {
+                Ok(x) => { result = result.lcm(&x); },
+                Err(m) => { return Err(m); }
+            }
+        }
+        Ok(BigDecimal::from(result))
+    }
+
+    // Miller-Rabin: n - 1 = d * 2^s, and a prime n has each a^d = 1, or
+    //   a^(d * 2^r) = -1 for some r < s (mod n)
+    fn is_probable_prime(n: &BigInt) -> bool {
+        if n < &BigInt::from(2) {
+            return false;
+        }
+        for base in PRIME_TEST_BASES.iter() {
+            let base = BigInt::from(*base);
+            if n == &base {
+                return true;
+            }
+            if n.is_multiple_of(&base) {
+                return false;
+            }
+        }
+        let n_minus_one = n - BigInt::one();
+        let mut d = n_minus_one.clone();
+        let mut s = 0;
+        while d.is_even() {
+            d /= 2;
+            s += 1;
+        }
+        'bases: for base in PRIME_TEST_BASES.iter() {
+            let mut x = BigInt::from(*base).modpow(&d, n);
+            if x.is_one() || x == n_minus_one {
+                continue;
+            }
+            for _ in 1..s {
+                x = x.modpow(&BigInt::from(2), n);
+                if x == n_minus_one {
+                    continue 'bases;
+                }
+            }
+            return false;
+        }
+        true
+    }
+
+    // 1 for a prime, 0 otherwise
+    pub fn isprime(x: &BigDecimal) -> Result<BigDecimal, String> {
+        match to_number_theory_integer(x, "isprime") {
+            Ok(n) if is_probable_prime(&n) => Ok(BigDecimal::one()),
+            Ok(_) => Ok(BigDecimal::zero()),
+            Err(m) => Err(m)
+        }
+    }
+
+    // a factor of a composite n, other than 1 and n, if one is found in time
+    fn pollard_rho(n: &BigInt) -> Option<BigInt> {
+        let mut steps = 0;
+        let mut c = BigInt::one();
+        while steps < MAX_RHO_STEPS {
+            let step = |x: &BigInt| (x * x + &c).mod_floor(n);
+            let (mut x, mut y) = (BigInt::from(2), BigInt::from(2));
+            loop {
+                x = step(&x);
+                y = step(&step(&y));
+                steps += 1;
+                let d = (&x - &y).gcd(n);
+                if d == *n || steps >= MAX_RHO_STEPS {
+                    break;
+                }
+                if !d.is_one() {
+                    return Some(d);
+                }
+            }
+            c += 1;
+        }
+        None
+    }
+
+    // the prime factors of n with their powers, smallest first -- a negative
+    //   n has the factors of -n
+    pub fn prime_factors(n: &BigInt) -> Result<Vec<(BigInt, u32)>, String> {
+        let mut remaining = match check_factorable(n) {
+            Ok(_) => n.abs(),
+            Err(m) => { return Err(m); }
+        };
+        let mut primes = Vec::<BigInt>::new();
+        let mut divisor = BigInt::from(2);
+        while divisor <= BigInt::from(MAX_TRIAL_DIVISOR) && &divisor * &divisor <= remaining {
+            while remaining.is_multiple_of(&divisor) {
+                primes.push(divisor.clone());
+                remaining /= &divisor;
+            }
+            divisor += if divisor == BigInt::from(2) { 1 } else { 2 };
+        }
+        let mut composites = vec![remaining];
+        while let Some(m) = composites.pop() {
+            if m.is_one() {
+                continue;
+            }
+            if is_probable_prime(&m) {
+                primes.push(m);
+                continue;
+            }
+            match pollard_rho(&m) {
+                Some(d) => {
+                    composites.push(&m / &d);
+                    composites.push(d);
+                },
+                None => { return Err(format!("[{}] has a prime factor too large to find", n)); }
+            }
+        }
+        primes.sort();
+        let mut factors = Vec::<(BigInt, u32)>::new();
+        for p in primes.into_iter() {
+            match factors.last_mut() {
+                Some((last, power)) if *last == p => { *power += 1; },
+                _ => { factors.push((p, 1)); }
+            }
+        }
+        Ok(factors)
+    }
+
+    fn check_factorable(n: &BigInt) -> Result<(), String> {
+        if n.is_zero() {
+            return Err("0 has no prime factors".to_string());
+        }
+        if n.abs().to_string().len() > MAX_FACTOR_DIGITS {
+            return Err(format!("[factor] only works on integers of up to {} digits", MAX_FACTOR_DIGITS));
+        }
+        Ok(())
+    }
+
+    // the value is n itself, which is factored only once it's shown, when
+    //   it's the whole calculation -- so this only checks that it could be
+    pub fn factor(x: &BigDecimal) -> Result<BigDecimal, String> {
+        let n = match to_integer(x, "factor") {
+            Ok(n) => n,
+            Err(m) => { return Err(m); }
+        };
+        match check_factorable(&n) {
+            Ok(_) => Ok(x.clone()),
+            Err(m) => Err(m)
+        }
+    }
+
+    // the inverse of a modulo m, so that a * modinv(a, m) = 1 (mod m)
+    fn mod_inverse(a: &BigInt, m: &BigInt) -> Result<BigInt, String> {
+        let extended = a.mod_floor(m).extended_gcd(m);
+        if !extended.gcd.is_one() {
+            return Err(format!("[{}] has no inverse modulo [{}], since they share the factor [{}]", a, m, extended.gcd));
+        }
+        Ok(extended.x.mod_floor(m))
+    }
+
+    pub fn modinv(args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        let (a, m) = match (to_number_theory_integer(&args[0], "modinv"), to_modulus(&args[1], "modinv")) {
+            (Ok(a), Ok(m)) => (a, m),
+            (Err(m), _) | (_, Err(m)) => { return Err(m); }
+        };
+        match mod_inverse(&a, &m) {
+            Ok(x) => Ok(BigDecimal::from(x)),
+            Err(m) => Err(m)
+        }
+    }
+
+    // a negative exponent is a power of the inverse of the base
+    pub fn modpow(args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        let (base, exponent, m) = match (to_number_theory_integer(&args[0], "modpow"),
+                to_number_theory_integer(&args[1], "modpow"), to_modulus(&args[2], "modpow")) {
+            (Ok(b), Ok(e), Ok(m)) => (b, e, m),
+            (Err(m), _, _) | (_, Err(m), _) | (_, _, Err(m)) => { return Err(m); }
+        };
+        let base = if exponent.is_negative() {
+            match mod_inverse(&base, &m) {
+                Ok(x) => x,
+                Err(m) => { return Err(m); }
+            }
+        } else {
+            base.mod_floor(&m)
+        };
+        Ok(BigDecimal::from(base.modpow(&exponent.abs(), &m)))
+    }
+
+    // the bitwise operations treat negative values as two's complement with
+    //   infinitely many leading ones, so "~x" is "-x - 1"
+    pub fn bit_and(a: &BigDecimal, b: &BigDecimal) -> Result<BigDecimal, String> {
+        let a_int = match to_integer(a, "&") {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        let b_int = match to_integer(b, "&") {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        Ok(BigDecimal::from(a_int & b_int))
+    }
+
+    pub fn bit_or(a: &BigDecimal, b: &BigDecimal) -> Result<BigDecimal, String> {
+        let a_int = match to_integer(a, "|") {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        let b_int = match to_integer(b, "|") {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        Ok(BigDecimal::from(a_int | b_int))
+    }
+
+    pub fn bit_xor(a: &BigDecimal, b: &BigDecimal) -> Result<BigDecimal, String> {
+        let a_int = match to_integer(a, "xor") {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        let b_int = match to_integer(b, "xor") {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        Ok(BigDecimal::from(a_int ^ b_int))
+    }
+
+    pub fn bit_not(x: &BigDecimal) -> Result<BigDecimal, String> {
+        let x_int = match to_integer(x, "~") {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        Ok(BigDecimal::from(!x_int))
+    }
+
+    fn to_shift_bits(bits: &BigDecimal, op: &str) -> Result<usize, String> {
+        let bits_int = match to_integer(bits, op) {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        match bits_int.to_u64() {
+            Some(n) if n <= MAX_SHIFT_BITS => Ok(n as usize),
+            Some(_) => Err(format!("cannot shift by more than {} bits", MAX_SHIFT_BITS)),
+            None => Err(format!("cannot shift by [{}] bits", bits))
+        }
+    }
+
+    pub fn shift_left(x: &BigDecimal, bits: &BigDecimal) -> Result<BigDecimal, String> {
+        let x_int = match to_integer(x, "<<") {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        let n = match to_shift_bits(bits, "<<") {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        Ok(BigDecimal::from(x_int << n))
+    }
+
+    // an arithmetic shift, which rounds toward negative infinity like "//"
+    pub fn shift_right(x: &BigDecimal, bits: &BigDecimal) -> Result<BigDecimal, String> {
+        let x_int = match to_integer(x, ">>") {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        let n = match to_shift_bits(bits, ">>") {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        Ok(BigDecimal::from(x_int >> n))
+    }
+
+    // x as stored in a word of the given number of bits (in two's complement
+    //   if signed), along with whether it had to wrap around to fit
+    pub fn wrap_to_word(x: &BigDecimal, bits: u32, signed: bool) -> Result<(BigInt, bool), String> {
+        if !x.is_integer() {
+            return Err(format!("a {}-bit word can only hold integers, not [{}]", bits, x));
+        }
+        let (x_int, _) = x.with_scale(0).as_bigint_and_exponent();
+        let modulus = BigInt::one() << (bits as usize);
+        let mut wrapped = x_int.mod_floor(&modulus);
+        if signed && wrapped >= (&modulus >> 1usize) {
+            wrapped -= &modulus;
+        }
+        let overflowed = wrapped != x_int;
+        Ok((wrapped, overflowed))
+    }
+
+    pub fn pow(base: &BigDecimal, exponent: &BigDecimal) -> Result<BigDecimal, String> {
+        if exponent.is_integer() {
+            let n = match exponent.to_i64() {
+                Some(n) => n,
+                None => { return Err(format!("exponent [{}] is too large", exponent)); }
+            };
+            return pow_integer(base, n);
+        }
+        if base.is_negative() {
+            return Err(format!("cannot raise negative value [{}] to fractional power [{}]", base, exponent));
+        }
+        if base.is_zero() {
+            if exponent.is_positive() {
+                return Ok(BigDecimal::zero());
+            }
+            return Err("cannot raise zero to a negative power".to_string());
+        }
+        let wp = PRECISION + GUARD_DIGITS;
+        let log = match ln_with_prec(base, wp) {
+            Ok(x) => x,
+            Err(m) => { return Err(m); }
+        };
+        match exp_with_prec(&(exponent * log).with_precision(wp), wp) {
+            Ok(x) => Ok(finish(x)),
+            Err(m) => Err(m)
+        }
+    }
+
+    pub fn pow_integer(base: &BigDecimal, n: i64) -> Result<BigDecimal, String> {
+        if n == 0 {
+            return Ok(BigDecimal::one());
+        }
+        if base.is_zero() {
+            if n < 0 {
+                return Err("cannot raise zero to a negative power".to_string());
+            }
+            return Ok(BigDecimal::zero());
+        }
+        let abs_base = base.abs();
+        if abs_base != BigDecimal::one() &&
+                n.unsigned_abs().saturating_mul(abs_base.digits()) > MAX_POWER_DIGITS {
+            return Err(format!("result of [{}] to the power of [{}] would be too large", base, n));
+        }
+        // exponentiation by squaring
+        let mut remaining = n.unsigned_abs();
+        let mut square = base.clone();
+        let mut result = BigDecimal::one();
+        while remaining > 0 {
+            if remaining & 1 == 1 {
+                result = &result * &square;
+            }
+            remaining >>= 1;
+            if remaining > 0 {
+                square = square.square();
+            }
+        }
+        if n < 0 {
+            return Ok(BigDecimal::one() / result);
+        }
+        Ok(result)
+    }
+
+    pub fn exp(x: &BigDecimal) -> Result<BigDecimal, String> {
+        match exp_with_prec(x, PRECISION + GUARD_DIGITS) {
+            Ok(x) => Ok(finish(x)),
+            Err(m) => Err(m)
+        }
+    }
+
+    pub fn ln(x: &BigDecimal) -> Result<BigDecimal, String> {
+        match ln_with_prec(x, PRECISION + GUARD_DIGITS) {
+            Ok(x) => Ok(finish(x)),
+            Err(m) => Err(m)
+        }
+    }
+
+    fn exp_with_prec(x: &BigDecimal, prec: u64) -> Result<BigDecimal, String> {
+        if x.is_zero() {
+            return Ok(BigDecimal::one());
+        }
+        if x.abs() > BigDecimal::from(MAX_EXP_ARGUMENT) {
+            return Err(format!("exponential of [{}] is out of range", x));
+        }
+        // halve the argument until it's small, then square the result back up
+        //   the same number of times
+        let half = BigDecimal::from_str("0.5").unwrap();
+        let mut reduced = x.clone();
+        let mut halvings: u64 = 0;
+        while reduced.abs() > half {
+            reduced = reduced.half();
+            halvings += 1;
+        }
+        let wp = prec + halvings / 3 + GUARD_DIGITS;
+        let reduced = reduced.with_precision(wp);
+
+        let eps = epsilon(wp + 2);
+        let mut sum = BigDecimal::one();
+        let mut term = BigDecimal::one();
+        let mut n: u32 = 1;
+        loop {
+            term = (&term * &reduced / BigDecimal::from(n)).with_precision(wp);
+            if term.abs() < eps {
+                break;
+            }
+            sum += &term;
+            n += 1;
+        }
+        for _ in 0..halvings {
+            sum = sum.square().with_precision(wp);
+        }
+        Ok(sum.with_precision(prec))
+    }
+
+    // uses ln(m) = 2 * atanh((m-1)/(m+1)), which converges quickly for m
+    //   near 1, after pulling out powers of 10 and 2
+    fn ln_with_prec(x: &BigDecimal, prec: u64) -> Result<BigDecimal, String> {
+        if !x.is_positive() {
+            return Err(format!("logarithm of non-positive value [{}] is undefined", x));
+        }
+        if x == &BigDecimal::one() {
+            return Ok(BigDecimal::zero());
+        }
+        let wp = prec + GUARD_DIGITS;
+
+        // x = m * 10^decimal_exponent, with m in [0.1, 1)
+        let (digits, scale) = x.as_bigint_and_exponent();
+        let digit_count = x.digits() as i64;
+        let decimal_exponent = digit_count - scale;
+        let mut m = BigDecimal::new(digits, digit_count);
+
+        // then bring m into [0.5, 1) by doubling (at most 3 times)
+        let half = BigDecimal::from_str("0.5").unwrap();
+        let mut doublings: i64 = 0;
+        while m < half {
+            m = m.double();
+            doublings += 1;
+        }
+
+        let mut result = atanh_series_ln(&m, wp);
+        if decimal_exponent != 0 || doublings != 0 {
+            let ln2 = atanh_series_ln(&BigDecimal::from(2), wp);
+            if decimal_exponent != 0 {
+                // ln(10) = ln(1.25) + 3 ln(2)
+                let ln10 = atanh_series_ln(&BigDecimal::from_str("1.25").unwrap(), wp)
+                    + &ln2 * BigDecimal::from(3);
+                result += ln10 * BigDecimal::from(decimal_exponent);
+            }
+            result -= ln2 * BigDecimal::from(doublings);
+        }
+        Ok(result.with_precision(prec))
+    }
+
+    fn atanh_series_ln(m: &BigDecimal, wp: u64) -> BigDecimal {
+        let z = ((m - BigDecimal::one()) / (m + BigDecimal::one())).with_precision(wp);
+        let z_squared = z.square().with_precision(wp);
+        let eps = epsilon(wp + 2);
+        let mut power = z.clone();
+        let mut sum = z;
+        let mut n: u32 = 3;
+        loop {
+            power = (&power * &z_squared).with_precision(wp);
+            let term = (&power / BigDecimal::from(n)).with_precision(wp);
+            if term.abs() < eps {
+                break;
+            }
+            sum += term;
+            n += 2;
+        }
+        sum.double()
+    }
+}
+
+// exact fractions, for calculations like "(1/3)*3" that BigDecimal division
+//   can only approximate
+pub mod rational {
+    use bigdecimal::BigDecimal;
+    use bigdecimal::{One, Signed, ToPrimitive, Zero};
+    use num_bigint::BigInt;
+    use num_integer::Integer;
+    use crate::bigmath;
+    use crate::bigmath::RoundingMode;
+    use std::cmp::Ordering;
+    use std::collections::HashMap;
+
+    // an approximation walks the continued fraction of its range, which has
+    //   more terms the more digits the fractions have
+    const MAX_APPROXIMATE_DIGITS: usize = 1000;
+
+    // always kept in lowest terms, with a positive denominator
+    #[derive(Clone, PartialEq, Debug)]
+    pub struct Rational {
+        numer: BigInt,
+        denom: BigInt
+    }
+
+    impl Rational {
+        pub fn new(numer: BigInt, denom: BigInt) -> Result<Rational, String> {
+            if denom.is_zero() {
+                return Err("cannot divide by zero".to_string());
+            }
+            let divisor = numer.gcd(&denom);
+            let (mut numer, mut denom) = (numer / &divisor, denom / &divisor);
+            if denom.is_negative() {
+                numer = -numer;
+                denom = -denom;
+            }
+            Ok(Rational { numer, denom })
+        }
+
+        pub fn from_integer(n: BigInt) -> Rational {
+            Rational { numer: n, denom: BigInt::one() }
+        }
+
+        // every decimal is exactly a fraction over a power of ten
+        pub fn from_bigdecimal(x: &BigDecimal) -> Rational {
+            let (digits, scale) = x.as_bigint_and_exponent();
+            if scale <= 0 {
+                return Rational::from_integer(digits * BigInt::from(10).pow((-scale) as u32));
+            }
+            Rational::new(digits, BigInt::from(10).pow(scale as u32)).unwrap()
+        }
+
+        pub fn numer(&self) -> &BigInt {
+            &self.numer
+        }
+
+        pub fn denom(&self) -> &BigInt {
+            &self.denom
+        }
+
+        pub fn is_integer(&self) -> bool {
+            self.denom.is_one()
+        }
+
+        // only fractions whose denominators have no prime factors other than 2
+        //   and 5 can be written as a decimal that ends
+        pub fn has_terminating_decimal(&self) -> bool {
+            let mut denom = self.denom.clone();
+            for factor in [2, 5].iter() {
+                let factor = BigInt::from(*factor);
+                while denom.is_multiple_of(&factor) {
+                    denom /= &factor;
+                }
+            }
+            denom.is_one()
+        }
+
+        // the digits after the decimal point, as the digits that don't repeat
+        //   and the digits that then repeat forever (empty for a decimal that
+        //   ends), like ("1", "6") for 1/6 = 0.1666... -- or None if that
+        //   takes more than max_digits digits
+        pub fn fraction_digits(&self, max_digits: usize) -> Option<(String, String)> {
+            let mut remainder = self.numer.abs().mod_floor(&self.denom);
+            let mut digits = String::new();
+            // the first digit calculated from each remainder, since the digits
+            //   repeat from there once a remainder is seen again
+            let mut seen = HashMap::<BigInt, usize>::new();
+            while !remainder.is_zero() {
+                if let Some(start) = seen.get(&remainder) {
+                    return Some((digits[..*start].to_string(), digits[*start..].to_string()));
+                }
+                if digits.len() >= max_digits {
+                    return None;
+                }
+                seen.insert(remainder.clone(), digits.len());
+                let (digit, next_remainder) = (remainder * BigInt::from(10)).div_rem(&self.denom);
+                digits.push_str(&digit.to_string());
+                remainder = next_remainder;
+            }
+            Some((digits, String::new()))
+        }
+
+        // exact when the decimal ends, and otherwise rounded like a division
+        pub fn to_bigdecimal(&self, mode: RoundingMode) -> BigDecimal {
+            if self.has_terminating_decimal() {
+                let mut scale = BigInt::one();
+                let mut places = 0;
+                while !scale.is_multiple_of(&self.denom) {
+                    scale *= 10;
+                    places += 1;
+                }
+                return BigDecimal::new(&self.numer * (scale / &self.denom), places);
+            }
+            bigmath::divide(&BigDecimal::from(self.numer.clone()), &BigDecimal::from(self.denom.clone()), mode).unwrap()
+        }
+
+        pub fn neg(&self) -> Rational {
+            Rational { numer: -&self.numer, denom: self.denom.clone() }
+        }
+
+        pub fn add(&self, other: &Rational) -> Rational {
+            Rational::new(&self.numer * &other.denom + &other.numer * &self.denom, &self.denom * &other.denom).unwrap()
+        }
+
+        pub fn sub(&self, other: &Rational) -> Rational {
+            Rational::new(&self.numer * &other.denom - &other.numer * &self.denom, &self.denom * &other.denom).unwrap()
+        }
+
+        pub fn mul(&self, other: &Rational) -> Rational {
+            Rational::new(&self.numer * &other.numer, &self.denom * &other.denom).unwrap()
+        }
+
+        pub fn div(&self, other: &Rational) -> Result<Rational, String> {
+            Rational::new(&self.numer * &other.denom, &self.denom * &other.numer)
+        }
+
+        // the largest integer not greater than the fraction
+        pub fn floor(&self) -> BigInt {
+            self.numer.div_floor(&self.denom)
+        }
+
+        pub fn floor_div(&self, other: &Rational) -> Result<Rational, String> {
+            match self.div(other) {
+                Ok(q) => Ok(Rational::from_integer(q.floor())),
+                Err(m) => Err(m)
+            }
+        }
+
+        // like floor_div(), the result has the sign of the divisor
+        pub fn floor_mod(&self, other: &Rational) -> Result<Rational, String> {
+            match self.floor_div(other) {
+                Ok(q) => Ok(self.sub(&other.mul(&q))),
+                Err(m) => Err(m)
+            }
+        }
+
+        // only integer exponents keep the result exact
+        pub fn pow(&self, exponent: &Rational) -> Result<Rational, String> {
+            let n = match exponent.numer.to_i64() {
+                Some(n) if exponent.is_integer() => n,
+                _ => { return Err(format!("[{}/{}] is not an integer exponent, so the power cannot be kept exact", exponent.numer, exponent.denom)); }
+            };
+            if self.numer.is_zero() && n < 0 {
+                return Err("cannot raise zero to a negative power".to_string());
+            }
+            let numer = match bigmath::pow_integer(&BigDecimal::from(self.numer.clone()), n.abs()) {
+                Ok(x) => x.with_scale(0).as_bigint_and_exponent().0,
+                Err(m) => { return Err(m); }
+            };
+            let denom = match bigmath::pow_integer(&BigDecimal::from(self.denom.clone()), n.abs()) {
+                Ok(x) => x.with_scale(0).as_bigint_and_exponent().0,
+                Err(m) => { return Err(m); }
+            };
+            if n < 0 {
+                return Rational::new(denom, numer);
+            }
+            Rational::new(numer, denom)
+        }
+
+        // the terms [a0; a1, a2, ...] of the continued fraction
+        //   a0 + 1/(a1 + 1/(a2 + ...)), which always ends for a fraction
+        pub fn continued_fraction(&self) -> Vec<BigInt> {
+            let mut terms = Vec::<BigInt>::new();
+            let (mut numer, mut denom) = (self.numer.clone(), self.denom.clone());
+            while !denom.is_zero() {
+                let (term, remainder) = numer.div_mod_floor(&denom);
+                terms.push(term);
+                numer = denom;
+                denom = remainder;
+            }
+            terms
+        }
+
+        // each convergent is the best approximation with a denominator up to
+        //   its own, and the last is the fraction itself
+        pub fn convergents(&self) -> Vec<Rational> {
+            let mut convergents = Vec::<Rational>::new();
+            let (mut numer, mut prev_numer) = (BigInt::one(), BigInt::zero());
+            let (mut denom, mut prev_denom) = (BigInt::zero(), BigInt::one());
+            for term in self.continued_fraction().iter() {
+                let next_numer = term * &numer + &prev_numer;
+                let next_denom = term * &denom + &prev_denom;
+                prev_numer = numer;
+                prev_denom = denom;
+                numer = next_numer;
+                denom = next_denom;
+                convergents.push(Rational { numer: numer.clone(), denom: denom.clone() });
+            }
+            convergents
+        }
+
+        // the fraction with the smallest denominator that is within the
+        //   tolerance, like 355/113 for 3.14159265 within 1e-6
+        pub fn approximate(&self, tolerance: &Rational) -> Result<Rational, String> {
+            if tolerance.numer.is_negative() {
+                return Err("the tolerance of an approximation cannot be negative".to_string());
+            }
+            if self.digits() > MAX_APPROXIMATE_DIGITS || tolerance.digits() > MAX_APPROXIMATE_DIGITS {
+                return Err(format!("[approx] only works on fractions of up to {} digits", MAX_APPROXIMATE_DIGITS));
+            }
+            Ok(Rational::simplest_between(&self.sub(tolerance), &self.add(tolerance)))
+        }
+
+        // the digits of the numerator or the denominator, whichever has more
+        fn digits(&self) -> usize {
+            self.numer.abs().max(self.denom.clone()).to_string().len()
+        }
+
+        // the simplest fraction that rounds to x at the given number of
+        //   significant digits, which finds a fraction again from a decimal
+        //   that had to be cut short, like 0.3333...3 back to 1/3
+        pub fn from_rounded_bigdecimal(x: &BigDecimal, digits: u64) -> Result<Rational, String> {
+            let (value, scale) = x.as_bigint_and_exponent();
+            if value.is_zero() {
+                return Ok(Rational::from_integer(value));
+            }
+            let ulp_power = value.abs().to_string().len() as i64 - scale - digits as i64;
+            let ulp = if ulp_power >= 0 {
+                Rational::from_integer(BigInt::from(10).pow(ulp_power as u32))
+            } else {
+                Rational::new(BigInt::one(), BigInt::from(10).pow((-ulp_power) as u32)).unwrap()
+            };
+            let half_ulp = ulp.mul(&Rational::new(BigInt::one(), BigInt::from(2)).unwrap());
+            Rational::from_bigdecimal(x).approximate(&half_ulp)
+        }
+
+        // the simplest fraction between lo and hi (inclusive) is an integer if
+        //   there's one in range, and otherwise shares the whole part of both
+        //   and takes its fractional part from the simplest fraction between
+        //   the reciprocals of theirs -- so each whole part found is the next
+        //   term of its continued fraction
+        fn simplest_between(lo: &Rational, hi: &Rational) -> Rational {
+            if !lo.numer.is_positive() && !hi.numer.is_negative() {
+                return Rational::from_integer(BigInt::zero());
+            }
+            if hi.numer.is_negative() {
+                return Rational::simplest_between(&hi.neg(), &lo.neg()).neg();
+            }
+            // lo is a/b and hi is c/d, and taking the whole part away and
+            //   flipping keeps them in lowest terms, like Euclid's algorithm
+            let (mut a, mut b) = (lo.numer.clone(), lo.denom.clone());
+            let (mut c, mut d) = (hi.numer.clone(), hi.denom.clone());
+            let mut terms = Vec::<BigInt>::new();
+            loop {
+                if b.is_one() {
+                    terms.push(a);
+                    break;
+                }
+                let (whole, lo_rem) = a.div_rem(&b);
+                let next = &whole + BigInt::one();
+                if c >= &next * &d {
+                    terms.push(next);
+                    break;
+                }
+                let hi_rem = c - &whole * &d;
+                terms.push(whole);
+                (a, b, c, d) = (d, hi_rem, b, lo_rem);
+            }
+            // built up from the terms like the convergents are, which are
+            //   already in lowest terms
+            let (mut numer, mut prev_numer) = (BigInt::one(), BigInt::zero());
+            let (mut denom, mut prev_denom) = (BigInt::zero(), BigInt::one());
+            for term in terms.iter() {
+                let next_numer = term * &numer + &prev_numer;
+                let next_denom = term * &denom + &prev_denom;
+                prev_numer = numer;
+                prev_denom = denom;
+                numer = next_numer;
+                denom = next_denom;
+            }
+            Rational { numer, denom }
+        }
+    }
+
+    // two fractions in lowest terms are equal only if their numerators and
+    //   denominators are, so the derived PartialEq is exact
+    impl Eq for Rational {}
+
+    impl Ord for Rational {
+        // denominators are positive, so cross-multiplying keeps the order
+        fn cmp(&self, other: &Rational) -> Ordering {
+            (&self.numer * &other.denom).cmp(&(&other.numer * &self.denom))
+        }
+    }
+
+    impl PartialOrd for Rational {
+        fn partial_cmp(&self, other: &Rational) -> Option<Ordering> {
+            Some(self.cmp(other))
         }
     }
-}
\ No newline at end of file
+}
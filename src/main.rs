@@ -3,25 +3,77 @@ use termion::color;
 use termion::event::Key;
 use termion::input::TermRead;
 use termion::raw::IntoRawMode;
-use evalexpr::eval_float;
+use evalexpr::eval_float_with_context;
 use evalexpr::error::EvalexprResult;
+use evalexpr::error::EvalexprError;
+use evalexpr::{Context, Function, HashMapContext, Value};
 use rust_calc_term::tree::NodeIndex;
 use rust_calc_term::tree::Tree;
 use rust_calc_term::tree::TreeNode;
 use rust_calc_term::tree::PostOrderIter;
 use rust_calc_term::tree::ChildSide;
+use rust_calc_term::bigmath;
+use rust_calc_term::bigmath::RoundingMode;
+use rust_calc_term::bigmath::AngleUnit;
+use rust_calc_term::rational::Rational;
 use bigdecimal::BigDecimal;
+use bigdecimal::Signed;
+use bigdecimal::ToPrimitive;
+use bigdecimal::Zero;
+use num_bigint::BigInt;
+use std::collections::BTreeMap;
 use std::str::FromStr;
 
+type CalcFunction = fn(&BigDecimal) -> Result<BigDecimal, String>;
+type FloatFunction = fn(f64) -> f64;
+type CalcMultiArgFunction = fn(&[BigDecimal]) -> Result<BigDecimal, String>;
+type FloatMultiArgFunction = fn(&[f64]) -> f64;
+type CalcAngleFunction = fn(&BigDecimal, AngleUnit) -> Result<BigDecimal, String>;
+
 enum CalcKey {
     Key(char),
     Delete
 }
 
+// the size of the integers in programmer mode, like "u8" or "i32"
+#[derive(Clone, Copy, PartialEq, Debug)]
+struct CalcWordSize {
+    bits: u32,
+    signed: bool
+}
+
+// how many digits results are rounded to
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum CalcPrecision {
+    SignificantDigits(u64),
+    DecimalPlaces(u64)
+}
+
+// how the result of an exact calculation with fractions is shown
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum CalcFractionFormat {
+    // "7/3"
+    Fraction,
+    // "2 1/3"
+    Mixed,
+    // "2.333..." (but still calculated exactly, so "(1/3)*3" is "1")
+    Decimal
+}
+
 #[derive(PartialEq, Debug)]
 enum CalcResult {
     Float(f64),
-    Integer(i64),
+    // the exact result from the bigdecimal tree, however many digits it has
+    Decimal(BigDecimal),
+    // the result of an exact calculation with fractions
+    Fraction(Rational, CalcFractionFormat),
+    // a programmer mode result, with the word size it was wrapped to fit and
+    //   whether it overflowed
+    Word(BigInt, CalcWordSize, bool),
+    // an integer with its prime factors and their powers, from "factor(n)"
+    Factors(BigInt, Vec<(BigInt, u32)>),
+    // the result of a comparison or logical operator, like "x > 2 and x < 5"
+    Boolean(bool),
     Error(String)
 }
 
@@ -32,16 +84,21 @@ enum CalcJumpToken {
     Dot,
     Space,
     Operator,
-    Paren
+    Paren,
+    Letter,
+    // a whole prefixed integer literal, like "0xFF", is one item
+    BaseLiteral
 }
 
 impl CalcJumpToken {
-    const TOKEN_CHARS: [(CalcJumpToken, &'static str); 5] = [
+    const TOKEN_CHARS: [(CalcJumpToken, &'static str); 7] = [
         (CalcJumpToken::Digit, "0123456789"),
         (CalcJumpToken::Dot, "."),
         (CalcJumpToken::Space, " "),
-        (CalcJumpToken::Operator, "+-*/"),
+        (CalcJumpToken::Operator, "+-*/^%&|~<>=!°"),
         (CalcJumpToken::Paren, "()"),
+        (CalcJumpToken::Letter, CalcParseToken::IDENTIFIER_CHARS),
+        (CalcJumpToken::Letter, "#"),
     ];
 
     fn get_token_matching_char(c: char) -> Option<CalcJumpToken> {
@@ -50,7 +107,32 @@ impl CalcJumpToken {
                 return Some(*t);
             }
         }
-        return None;
+        None
+    }
+
+    // the (start, end) char positions of each prefixed integer literal in
+    //   the calc, where end is the position just after the literal
+    fn find_base_literals(calc: &str) -> Vec<(usize, usize)> {
+        let chars: Vec<char> = calc.chars().collect();
+        let mut literals = vec![];
+        let mut i = 0;
+        while i + 2 < chars.len() {
+            let starts_value = i == 0 ||
+                !(chars[i - 1] == '.' || chars[i - 1].is_ascii_digit() || CalcParseToken::IDENTIFIER_CHARS.contains(chars[i - 1]));
+            let radix = CalcParseToken::get_base_prefix_radix(chars[i + 1]);
+            if chars[i] == '0' && starts_value && radix.is_some_and(|r| chars[i + 2].is_digit(r)) {
+                let radix = radix.unwrap();
+                let mut end = i + 2;
+                while end < chars.len() && (chars[end].is_digit(radix) || chars[end] == '_') {
+                    end += 1;
+                }
+                literals.push((i, end));
+                i = end;
+            } else {
+                i += 1;
+            }
+        }
+        literals
     }
 }
 
@@ -59,17 +141,88 @@ enum CalcParseToken {
     Value,
     Operator,
     OpenParen,
-    CloseParen
+    CloseParen,
+    // a function name when followed by an open paren, like "sqrt(2)",
+    //   otherwise a variable name or a reference to a previous result, like
+    //   "ans" or "#2"
+    Identifier,
+    // a postfix percent sign, like "15%", a factorial, like "5!", or a
+    //   degree sign, like "45°"
+    Postfix,
+    // a prefix operator with a single operand, like the "~" in "~5" -- a
+    //   "-" or "+" is tokenized as an operator, and becomes a prefix one
+    //   when it's placed in the tree where an operand is expected
+    UnaryOperator
 }
 
 impl CalcParseToken {
-    const OPERATORS: &'static str = "+-*/";
+    const OPERATORS: &'static str = "+-*/^%&|<>=";
+
+    // higher numbers bind more tightly
+    // "," separates function arguments, and binds the least tightly of all
+    // "and" and "or" bind less tightly than comparisons, which bind less
+    //   tightly than arithmetic, so "x + 1 > 2 and x < 5" needs no parens --
+    //   comparisons don't chain though, so "1 < x < 3" is "(1 < x) < 3"
+    // the bitwise operators follow C, so "1 | 6 & 3 << 1" is "1 | (6 & (3 << 1))"
+    // a "%" typed as the remainder operator becomes "mod" when tokenized, to
+    //   tell it apart from a percent sign (see is_remainder_sign())
+    // "of" multiplies a percentage, like "15% of 80"
+    const OPERATOR_PRECEDENCE: [(&'static str, u8); 22] = [
+        (",", 0),
+        ("or", 1),
+        ("and", 2),
+        ("<", 4),
+        ("<=", 4),
+        (">", 4),
+        (">=", 4),
+        ("==", 4),
+        ("!=", 4),
+        ("|", 5),
+        ("xor", 6),
+        ("&", 7),
+        ("<<", 8),
+        (">>", 8),
+        ("+", 9),
+        ("-", 9),
+        ("*", 10),
+        ("/", 10),
+        ("//", 10),
+        ("mod", 10),
+        ("of", 10),
+        ("^", 12),
+    ];
+
+    // comparisons and logical operators give 1 for true and 0 for false
+    const BOOLEAN_OPERATORS: [&'static str; 8] = ["<", "<=", ">", ">=", "==", "!=", "and", "or"];
+
+    // prefix operators bind more tightly than any binary operator except "^",
+    //   so "~2^2" is "~(2^2)" while "~2*2" is "(~2)*2", and "-x^2" is "-(x^2)"
+    // "not" binds less tightly than a comparison but more tightly than "and",
+    //   so "not x == 1 and y" is "(not (x == 1)) and y"
+    fn get_unary_operator_precedence(op: &str) -> u8 {
+        if op == "not" {
+            return 3;
+        }
+        11
+    }
+
+    // identifiers start with one of these, and may also contain digits after
+    //   the first char (like "log10")
+    const IDENTIFIER_CHARS: &'static str = "abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ_";
+
+    // "#" starts a reference to a previous result, like "#2" -- "$" can't
+    //   be used for that since it's dropped as a currency sign
+    const HISTORY_REFERENCE_CHAR: char = '#';
 
-    const TOKEN_CHARS: [(CalcParseToken, &'static str); 4] = [
+    const TOKEN_CHARS: [(CalcParseToken, &'static str); 8] = [
         (CalcParseToken::Value, ".0123456789"),
         (CalcParseToken::Operator, CalcParseToken::OPERATORS),
+        (CalcParseToken::UnaryOperator, "~"),
+        (CalcParseToken::Postfix, "!°"),
         (CalcParseToken::OpenParen, "("),
-        (CalcParseToken::CloseParen, ")")
+        (CalcParseToken::CloseParen, ")"),
+        (CalcParseToken::Identifier, CalcParseToken::IDENTIFIER_CHARS),
+        (CalcParseToken::Identifier, "#")
     ];
 
     fn get_token_matching_char(c: char) -> Option<CalcParseToken> {
@@ -78,7 +231,107 @@ impl CalcParseToken {
                 return Some(*t);
             }
         }
-        return None;
+        None
+    }
+
+    fn get_operator_precedence(op: &str) -> Option<u8> {
+        for (o, p) in CalcParseToken::OPERATOR_PRECEDENCE.iter() {
+            if *o == op {
+                return Some(*p);
+            }
+        }
+        None
+    }
+
+    // the "x" in "0xFF", "o" in "0o755", or "b" in "0b1011"
+    fn get_base_prefix_radix(c: char) -> Option<u32> {
+        match c {
+            'x' | 'X' => Some(16),
+            'o' | 'O' => Some(8),
+            'b' | 'B' => Some(2),
+            _ => None
+        }
+    }
+
+    // a decimal with its repeating digits in parens, like "0.(3)" for 1/3 or
+    //   "-1.2(34)" for -1.23434...
+    fn parse_repeating_decimal(s: &str) -> Option<Rational> {
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(u) => (true, u),
+            None => (false, s)
+        };
+        let (whole, rest) = match unsigned.split_once('.') {
+            Some(x) => x,
+            None => { return None; }
+        };
+        let (non_repeating, repetend) = match rest.split_once('(') {
+            Some((n, r)) if r.ends_with(')') => (n, &r[..r.len() - 1]),
+            _ => { return None; }
+        };
+        let is_digits = |d: &str| d.chars().all(|c| c.is_ascii_digit());
+        if repetend.is_empty() || !is_digits(whole) || !is_digits(non_repeating) || !is_digits(repetend) {
+            return None;
+        }
+        // 1.2(34) is (1234 - 12) / 990, since the repeating digits are a
+        //   repetend over as many 9s, shifted right past the non-repeating digits
+        let parse = |d: &str| BigInt::from_str(&format!("0{}", d)).unwrap();
+        let numer = parse(&format!("{}{}{}", whole, non_repeating, repetend)) - parse(&format!("{}{}", whole, non_repeating));
+        let nines = BigInt::from(10).pow(repetend.len() as u32) - 1;
+        let denom = nines * BigInt::from(10).pow(non_repeating.len() as u32);
+        let value = match Rational::new(numer, denom) {
+            Ok(v) => v,
+            Err(_) => { return None; }
+        };
+        if negative {
+            return Some(value.neg());
+        }
+        Some(value)
+    }
+
+    // an integer in base 16, 8, or 2, like "0xFF", "-0o755" or "0b1010_1010",
+    //   with "_" allowed between digits
+    fn parse_base_literal(s: &str) -> Option<BigInt> {
+        let (negative, unsigned) = match s.strip_prefix('-') {
+            Some(u) => (true, u),
+            None => (false, s)
+        };
+        let mut chars = unsigned.chars();
+        if chars.next() != Some('0') {
+            return None;
+        }
+        let radix = match chars.next().and_then(CalcParseToken::get_base_prefix_radix) {
+            Some(r) => r,
+            None => { return None; }
+        };
+        let digits = chars.as_str();
+        if digits.is_empty() || digits.starts_with('_') || digits.ends_with('_') ||
+                !digits.chars().all(|c| c.is_digit(radix) || c == '_') {
+            return None;
+        }
+        let value = match BigInt::parse_bytes(digits.replace("_", "").as_bytes(), radix) {
+            Some(v) => v,
+            None => { return None; }
+        };
+        if negative {
+            return Some(-value);
+        }
+        Some(value)
+    }
+
+    fn is_sign(s: &str) -> bool {
+        s == "-" || s == "+"
+    }
+
+    fn is_history_reference(s: &str) -> bool {
+        match s.strip_prefix(CalcParseToken::HISTORY_REFERENCE_CHAR) {
+            Some(digits) => !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()),
+            None => false
+        }
+    }
+
+    // "2^3^2" is "2^(3^2)", all other operators are evaluated left to right
+    fn is_right_associative(op: &str) -> bool {
+        op == "^"
     }
 
     fn get_token_matching_str(s: &str) -> Option<CalcParseToken> {
@@ -88,12 +341,42 @@ impl CalcParseToken {
         if s == ")" {
             return Some(CalcParseToken::CloseParen);
         }
+        if CalcParseToken::parse_repeating_decimal(s).is_some() {
+            return Some(CalcParseToken::Value);
+        }
         if s.contains('(') || s.contains(')') {
             return None;
         }
-        if s.len() == 1 && CalcParseToken::OPERATORS.contains(s) {
+        if s == "%" || s == "!" || s == "°" {
+            return Some(CalcParseToken::Postfix);
+        }
+        if s == "~" || s == "not" {
+            return Some(CalcParseToken::UnaryOperator);
+        }
+        if CalcParseToken::get_operator_precedence(s).is_some() {
             return Some(CalcParseToken::Operator);
         }
+        if s.starts_with(|c| CalcParseToken::IDENTIFIER_CHARS.contains(c)) &&
+                s.chars().all(|c| c.is_ascii_digit() || CalcParseToken::IDENTIFIER_CHARS.contains(c)) {
+            return Some(CalcParseToken::Identifier);
+        }
+        if CalcParseToken::is_history_reference(s) {
+            return Some(CalcParseToken::Identifier);
+        }
+        if CalcParseToken::parse_base_literal(s).is_some() {
+            return Some(CalcParseToken::Value);
+        }
+        // a value may be in scientific notation, like "6.022e23" or "1.5E-9"
+        let (s, exponent) = match s.find(['e', 'E']) {
+            Some(i) => (&s[..i], Some(&s[i + 1..])),
+            None => (s, None)
+        };
+        if let Some(exponent) = exponent {
+            let exponent_digits = exponent.strip_prefix(['-', '+']).unwrap_or(exponent);
+            if exponent_digits.is_empty() || !exponent_digits.chars().all(|c| c.is_ascii_digit()) {
+                return None;
+            }
+        }
         if (
                 s.starts_with("-") || s.starts_with(".") ||
                 s.starts_with('0') || s.starts_with('1') || s.starts_with('2') ||
@@ -108,11 +391,12 @@ impl CalcParseToken {
             (
                 s.matches('-').count() == 0 ||
                 (s.matches('-').count() == 1 && s.starts_with("-"))
-            ) {
+            ) &&
+            s.chars().all(|c| "-.0123456789".contains(c)) {
             return Some(CalcParseToken::Value);
         }
 
-        return None;
+        None
     }
 }
 
@@ -130,18 +414,152 @@ impl CalcEvalItem {
     }
 }
 
+// what one evaluation keeps track of, through all the calls it makes to
+//   user-defined functions
+struct CalcEvalState {
+    // in programmer mode, each value is wrapped around to fit the word
+    word_size: Option<CalcWordSize>,
+    overflowed: bool,
+    // calls to user-defined functions so far, nested or not
+    function_calls: usize
+}
+
+impl CalcEvalState {
+    fn new(word_size: Option<CalcWordSize>) -> CalcEvalState {
+        CalcEvalState {
+            word_size,
+            overflowed: false,
+            function_calls: 0
+        }
+    }
+}
+
+// a value being calculated is kept as an exact fraction for as long as it
+//   can be, and otherwise as a decimal, along with why it isn't exact
+#[derive(Clone, Debug)]
+enum CalcValue {
+    Exact(Rational),
+    Inexact(BigDecimal, String)
+}
+
+impl CalcValue {
+    fn to_bigdecimal(&self, rounding_mode: RoundingMode) -> BigDecimal {
+        match self {
+            CalcValue::Exact(x) => x.to_bigdecimal(rounding_mode),
+            CalcValue::Inexact(x, _) => x.clone()
+        }
+    }
+
+    // an inexact value is only known to bigmath::PRECISION digits, so it's
+    //   shown to no more than those, like "2pi" agreeing with "tau"
+    fn to_known_digits(&self, rounding_mode: RoundingMode) -> BigDecimal {
+        match self {
+            CalcValue::Exact(x) => x.to_bigdecimal(rounding_mode),
+            CalcValue::Inexact(x, _) => bigmath::round_to_significant_digits(x, bigmath::PRECISION, rounding_mode)
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        match self {
+            CalcValue::Exact(x) => x.numer().is_zero(),
+            CalcValue::Inexact(x, _) => x.is_zero()
+        }
+    }
+
+    fn is_integer(&self) -> bool {
+        match self {
+            CalcValue::Exact(x) => x.is_integer(),
+            CalcValue::Inexact(x, _) => x.is_integer()
+        }
+    }
+
+    // the same integer, and just as exact
+    fn with_integer(&self, n: BigInt) -> CalcValue {
+        match self {
+            CalcValue::Exact(_) => CalcValue::Exact(Rational::from_integer(n)),
+            CalcValue::Inexact(_, m) => CalcValue::Inexact(BigDecimal::from(n), m.clone())
+        }
+    }
+
+    // the result is exact when all the operands are and the exact operation
+    //   works, like "1/3 * 3" -- otherwise it's found from their decimals,
+    //   like "sqrt(2)" or "pi/2"
+    fn apply<E, D>(operands: &[CalcValue], rounding_mode: RoundingMode, exact: E, decimal: D) -> Result<CalcValue, String>
+            where E: FnOnce(&[Rational]) -> Result<Rational, String>,
+                  D: FnOnce(&[BigDecimal]) -> Result<BigDecimal, String> {
+        let mut exact_operands: Vec<Rational> = vec![];
+        let mut inexact_reason: Option<String> = None;
+        for operand in operands.iter() {
+            match operand {
+                CalcValue::Exact(x) => { exact_operands.push(x.clone()); },
+                CalcValue::Inexact(_, m) => {
+                    inexact_reason = Some(m.clone());
+                    break;
+                }
+            }
+        }
+        let inexact_reason = match inexact_reason {
+            Some(m) => m,
+            None => match exact(&exact_operands) {
+                Ok(x) => { return Ok(CalcValue::Exact(x)); },
+                Err(m) => m
+            }
+        };
+        let decimal_operands: Vec<BigDecimal> = operands.iter().map(|x| x.to_bigdecimal(rounding_mode)).collect();
+        match decimal(&decimal_operands) {
+            Ok(x) => Ok(CalcValue::Inexact(x, inexact_reason)),
+            Err(m) => Err(m)
+        }
+    }
+}
+
+// the left hand side of an "=" in a calc
+#[derive(Debug,PartialEq)]
+enum CalcAssignment {
+    // "rate = 0.0725"
+    Variable(String),
+    // "tip(amount, pct) = amount * pct / 100", with its parameter names
+    Function(String, Vec<String>)
+}
+
+// a function defined in the calc, with its body parsed once when defined
+struct CalcUserFunction {
+    params: Vec<String>,
+    body: String,
+    tree: Tree<CalcEvalItem>,
+}
+
 struct Calculator {
     calc: String,
     calc_pos: u16,
     prev_calcs: Vec<(String, CalcResult)>,
     selected_calc: u8,
     selected_equals: bool,
+    // exact values assigned with "name = expression", sorted by name for display
+    variables: BTreeMap<String, BigDecimal>,
+    user_functions: BTreeMap<String, CalcUserFunction>,
+    // results without their own "in hex" (or similar) are shown in this base
+    output_base: u32,
+    // programmer mode is on when results are wrapped to a word size
+    word_size: Option<CalcWordSize>,
+    // results are only rounded when a precision is set, but the rounding
+    //   mode also applies to divisions that don't terminate
+    precision: Option<CalcPrecision>,
+    rounding_mode: RoundingMode,
+    // calculations are done with exact fractions when this is set
+    rational_mode: Option<CalcFractionFormat>,
+    // variables assigned with exact fractions, which their values in
+    //   variables can only approximate
+    exact_variables: BTreeMap<String, Rational>,
+    // trig functions take angles in this unit, and inverse trig functions
+    //   give them in it
+    angle_mode: AngleUnit,
 }
 
 fn main() {
     let mut is_help_requested = false;
     let help_text_short: String = format!(
-        "{}{}[h: help] [ctrl+q: quit]{}{}",
+        "{}{}[?: help] [ctrl+q: quit]{}{}",
         color::Bg(color::AnsiValue::grayscale(5)),
         color::Fg(color::AnsiValue::grayscale(11)),
         color::Bg(color::Reset),
@@ -149,22 +567,35 @@ fn main() {
     let help_text_long: String = format!(
         "{}{}\
 Type an expression, like \"355/113\" or \"(9+8)/(7+6)\" and hit return!\n\r\
-previous calculations: [←↑↓→: select] [space: use selected] [a/z: show fewer/more prevs]\n\r\
-editing: [q/r: move to beg/end] [w/e: jump left/right to item edge]\n\r\
-other: [h: hide help] [ctrl+q: quit]{}{}",
+numbers: \"$1,000\", \"6.022e23\", \"1.5e-9\" (but \"2e\" is 2 times the constant e), \"0xFF\", \"0o755\", \"0b1011\", \"0xFFFF_FFFF\", \"0.(3)\" and \"1.2(34)\" repeat\n\r\
+counting: [\"5!\": factorial of a whole number] [\"nCr(52, 5)\": combinations] [\"nPr(10, 3)\": permutations]\n\r\
+number theory: [gcd, lcm: of any number of integers] [isprime(n): 1 or 0] [factor(n): like 2^3 * 3 * 5] [modpow(b, e, m)] [modinv(a, m)]\n\r\
+logic: [<, <=, >, >=, ==, !=: exact comparisons] [and, or, not] [\"if(x > 0, x, 0)\": only the chosen value is calculated] [\"near(a, b, 1e-9)\": within a tolerance, which is optional]\n\r\
+percent: \"200 + 15%\", \"200 - 15%\", \"80 * 25%\" and \"15% of 80\" (\"7 % 3\", \"7%-3\" or \"7 mod 3\" is a remainder)\n\r\
+integers: [&: and] [|: or] [xor] [~: not] [<<, >>: shift] (\"^\" is still a power, not xor)\n\r\
+multiplying: \"2(3+4)\", \"(1+2)(3+4)\" and \"3pi\" multiply just like \"*\", so \"1/2(3)\" is \"(1/2)*3\" (but \"1.5(2)\" repeats, so write \"1.5 (2)\" or \"1.5*2\")\n\r\
+functions: {} (separate arguments with \",\")\n\r\
+constants: {}\n\r\
+variables: [name = expression: assign] [name =: delete] [ans: last result] [#1, #2, ...: Nth most recent result]\n\r\
+your functions: [f(x, y) = expression: define] [f(x, y) =: delete]\n\r\
+output base: [\"... in hex\", \"... to bin\", \"... in base 36\": show one result in a base] [\"in hex\": show all results in a base] [ctrl+o: cycle dec/hex/oct/bin]\n\r\
+fractions: [\"... as fraction\", \"... as mixed\", \"... as decimal\": calculate one result exactly] [\"as fraction\": calculate all results exactly] [ctrl+f: cycle fraction/mixed/decimal/off]\n\r\
+approximations: [\"approx(3.14159265, 1e-6)\": simplest fraction within a tolerance] (select a result to see its convergents)\n\r\
+angles: [ctrl+d: cycle rad/deg/grad] [\"30deg\", \"1.2rad\", \"45°\": in any mode] [degrees(x), radians(x): convert] (trig functions take, and give, angles in the mode)\n\r\
+rounding: [\"digits 6\": significant digits] [\"places 2\": decimal places] [\"digits off\": don't round] [\"round half-up\", or half-even/down/up] [ctrl+r: cycle modes]\n\r\
+programmer mode: [ctrl+p: on/off] [ctrl+w: 8/16/32/64 bits] [ctrl+u: signed/unsigned] (results wrap around, and overflows are flagged)\n\r\
+previous calculations: [←↑↓→: select] [space: use selected] [pgup/pgdn: show fewer/more prevs]\n\r\
+editing: [home/end or ctrl+a/e: move to beg/end] [alt+b/f: jump left/right to item edge]\n\r\
+other: [?: hide help] [ctrl+q: quit]{}{}",
         color::Bg(color::AnsiValue::grayscale(5)),
         color::Fg(color::AnsiValue::grayscale(11)),
+        Calculator::get_function_names().join(" "),
+        Calculator::get_constant_names().join(" "),
         color::Bg(color::Reset),
         color::Fg(color::Reset));
     let mut help_text = &help_text_short;
 
-    let mut calc = Calculator {
-        calc: String::from(""),
-        calc_pos: 0,
-        prev_calcs: vec![],
-        selected_calc: 0,
-        selected_equals: false,
-    };
+    let mut calc = Calculator::new();
     let mut history_items: u8 = 10;
 
     let stdin = stdin();
@@ -197,12 +628,28 @@ other: [h: hide help] [ctrl+q: quit]{}{}",
             Key::Char('-') => calc.append_key_to_calc(&CalcKey::Key('-')),
             Key::Char('*') => calc.append_key_to_calc(&CalcKey::Key('*')),
             Key::Char('/') => calc.append_key_to_calc(&CalcKey::Key('/')),
+            Key::Char('^') => calc.append_key_to_calc(&CalcKey::Key('^')),
+            Key::Char('%') => calc.append_key_to_calc(&CalcKey::Key('%')),
+            Key::Char('&') => calc.append_key_to_calc(&CalcKey::Key('&')),
+            Key::Char('|') => calc.append_key_to_calc(&CalcKey::Key('|')),
+            Key::Char('~') => calc.append_key_to_calc(&CalcKey::Key('~')),
+            Key::Char('!') => calc.append_key_to_calc(&CalcKey::Key('!')),
+            Key::Char('°') => calc.append_key_to_calc(&CalcKey::Key('°')),
+            Key::Char('<') => calc.append_key_to_calc(&CalcKey::Key('<')),
+            Key::Char('>') => calc.append_key_to_calc(&CalcKey::Key('>')),
             Key::Char('(') => calc.append_key_to_calc(&CalcKey::Key('(')),
             Key::Char(')') => calc.append_key_to_calc(&CalcKey::Key(')')),
             Key::Char('.') => calc.append_key_to_calc(&CalcKey::Key('.')),
             Key::Char(',') => calc.append_key_to_calc(&CalcKey::Key(',')),
             Key::Char('$') => calc.append_key_to_calc(&CalcKey::Key('$')),
-            Key::Char('h') => {
+            Key::Char('=') => calc.append_key_to_calc(&CalcKey::Key('=')),
+            Key::Char('#') => calc.append_key_to_calc(&CalcKey::Key('#')),
+            // every letter is typed into the calc, for names like "sqrt" and "pi",
+            //   so the shortcuts that used to be letters are on keys that can't be
+            //   typed: h is "?", a/z are pgup/pgdn, q/r are home/end (or ctrl+a/e),
+            //   w/e are alt+b/f, and p is alt+p
+            Key::Char(c) if CalcParseToken::IDENTIFIER_CHARS.contains(*c) => calc.append_key_to_calc(&CalcKey::Key(*c)),
+            Key::Char('?') => {
                 is_help_requested = !is_help_requested;
                 if is_help_requested {
                     help_text = &help_text_long;
@@ -211,20 +658,16 @@ other: [h: hide help] [ctrl+q: quit]{}{}",
                 }
             },
             Key::Backspace => calc.append_key_to_calc(&CalcKey::Delete),
-            Key::Char('z') => {
-                if history_items < 100 && usize::from(history_items) < calc.prev_calcs.len() {
-                    history_items += 1;
-                }
+            Key::PageDown if history_items < 100 && usize::from(history_items) < calc.prev_calcs.len() => {
+                history_items += 1;
             },
-            Key::Char('a') => {
-                if !calc.prev_calcs.is_empty() {
-                    if usize::from(history_items) > calc.prev_calcs.len() {
-                        while usize::from(history_items) >= calc.prev_calcs.len() {
-                            history_items -= 1;
-                        }
-                    } else if history_items > 0 {
+            Key::PageUp if !calc.prev_calcs.is_empty() => {
+                if usize::from(history_items) > calc.prev_calcs.len() {
+                    while usize::from(history_items) >= calc.prev_calcs.len() {
                         history_items -= 1;
                     }
+                } else {
+                    history_items = history_items.saturating_sub(1);
                 }
             },
             Key::Char(' ') => {
@@ -240,28 +683,31 @@ other: [h: hide help] [ctrl+q: quit]{}{}",
             Key::Right => {
                 calc.move_cursor_right();
             },
-            Key::Char('q') => calc.move_cursor_home(),
-            Key::Char('r') => calc.move_cursor_end(),
-            Key::Up => {
-                if calc.selected_calc > 0 {
-                    calc.selected_calc -= 1;
-                }
+            Key::Home | Key::Ctrl('a') => calc.move_cursor_home(),
+            Key::End | Key::Ctrl('e') => calc.move_cursor_end(),
+            Key::Up if calc.selected_calc > 0 => {
+                calc.selected_calc -= 1;
+            },
+            Key::Down if calc.selected_calc < history_items &&
+                    usize::from(calc.selected_calc) < calc.prev_calcs.len() => {
+                calc.selected_calc += 1;
             },
-            Key::Down => {
-                if calc.selected_calc < history_items &&
-                        usize::from(calc.selected_calc) < calc.prev_calcs.len() {
-                    calc.selected_calc += 1;
-                }
-            }
             Key::Char('\n') => calc.perform_calculation(),
-            Key::Char('w') => calc.move_cursor_left_token(),
-            Key::Char('e') => calc.move_cursor_right_token(),
-            Key::Char('p') => { Calculator::parse_calc_to_tokens(&calc.calc); },
+            Key::Alt('b') => calc.move_cursor_left_token(),
+            Key::Alt('f') => calc.move_cursor_right_token(),
+            Key::Alt('p') => { let _ = Calculator::parse_calc_to_tokens(&calc.calc); },
+            Key::Ctrl('o') => calc.cycle_output_base(),
+            Key::Ctrl('p') => calc.toggle_programmer_mode(),
+            Key::Ctrl('w') => calc.cycle_word_bits(),
+            Key::Ctrl('u') => calc.toggle_word_signed(),
+            Key::Ctrl('r') => calc.cycle_rounding_mode(),
+            Key::Ctrl('f') => calc.cycle_rational_mode(),
+            Key::Ctrl('d') => calc.cycle_angle_mode(),
             //x => { calc.calc = format!("{:?}", x); }
             _ => ()
         }
 
-        write!(stdout, "{}{}{}\n",
+        writeln!(stdout, "{}{}{}",
             // clear the screen,
             termion::clear::All,
             // go to top left corner
@@ -285,9 +731,9 @@ other: [h: hide help] [ctrl+q: quit]{}{}",
                 }
             }
             line += 1;
-            let formatted = Calculator::format_prev_calculation(&output);
+            let formatted = Calculator::format_prev_calculation_in_base(output, calc.get_display_base(input));
             if is_selected_left {
-                write!(stdout, "{}{}{}{}{}{} = {}\n",
+                writeln!(stdout, "{}{}{}{}{}{} = {}",
                     termion::cursor::Goto(1,line.into()),
                     color::Bg(color::Blue),
                     color::Fg(color::Yellow),
@@ -296,7 +742,7 @@ other: [h: hide help] [ctrl+q: quit]{}{}",
                     color::Fg(color::Reset),
                     formatted).unwrap();
             } else if is_selected_right {
-                write!(stdout, "{}{} = {}{}{}{}{}\n",
+                writeln!(stdout, "{}{} = {}{}{}{}{}",
                     termion::cursor::Goto(1,line.into()),
                     input,
                     color::Bg(color::Blue),
@@ -305,15 +751,72 @@ other: [h: hide help] [ctrl+q: quit]{}{}",
                     color::Bg(color::Reset),
                     color::Fg(color::Reset)).unwrap();
             } else {
-                write!(stdout, "{}{} = {}\n",
+                writeln!(stdout, "{}{} = {}",
                     termion::cursor::Goto(1,line.into()),
                     input,
                     formatted).unwrap();
             }
         }
 
+        if !calc.variables.is_empty() {
+            line += 1;
+            writeln!(stdout, "{}{}",
+                termion::cursor::Goto(1,line.into()),
+                calc.format_variables()).unwrap();
+        }
+        if calc.output_base != 10 {
+            line += 1;
+            writeln!(stdout, "{}output base: {} [ctrl+o: change]",
+                termion::cursor::Goto(1,line.into()),
+                Calculator::get_base_name(calc.output_base)).unwrap();
+        }
+        if let Some(format) = calc.rational_mode {
+            line += 1;
+            writeln!(stdout, "{}exact fractions: shown as {} [ctrl+f: change]",
+                termion::cursor::Goto(1,line.into()),
+                Calculator::get_fraction_format_name(format)).unwrap();
+        }
+        if calc.precision.is_some() || calc.rounding_mode != RoundingMode::HalfEven {
+            line += 1;
+            writeln!(stdout, "{}{} [ctrl+r: change rounding]",
+                termion::cursor::Goto(1,line.into()),
+                calc.format_rounding()).unwrap();
+        }
+        if calc.angle_mode != AngleUnit::Radians {
+            line += 1;
+            writeln!(stdout, "{}angle mode: {} [ctrl+d: change]",
+                termion::cursor::Goto(1,line.into()),
+                Calculator::get_angle_unit_name(calc.angle_mode)).unwrap();
+        }
+        if let Some(word_size) = calc.word_size {
+            line += 1;
+            writeln!(stdout, "{}programmer mode: {} [ctrl+w: word size] [ctrl+u: signed/unsigned] [ctrl+p: off]",
+                termion::cursor::Goto(1,line.into()),
+                Calculator::get_word_size_name(word_size)).unwrap();
+            if let Some((value, result_word_size)) = calc.get_bit_grid_result() {
+                for grid_line in Calculator::format_bit_grid(value, result_word_size).iter() {
+                    line += 1;
+                    writeln!(stdout, "{}{}",
+                        termion::cursor::Goto(1,line.into()),
+                        grid_line).unwrap();
+                }
+            }
+        }
+        if let Some(x) = calc.get_convergents_result() {
+            line += 1;
+            writeln!(stdout, "{}{}",
+                termion::cursor::Goto(1,line.into()),
+                Calculator::format_convergents(&x)).unwrap();
+        }
+        if !calc.user_functions.is_empty() {
+            line += 1;
+            writeln!(stdout, "{}{}",
+                termion::cursor::Goto(1,line.into()),
+                calc.format_user_functions()).unwrap();
+        }
+
         line += 1;
-        write!(stdout, "{}{}\n",
+        writeln!(stdout, "{}{}",
             termion::cursor::Goto(1,line.into()),
             help_text).unwrap();
 
@@ -326,13 +829,289 @@ other: [h: hide help] [ctrl+q: quit]{}{}",
 }
 
 impl Calculator {
+    fn new() -> Calculator {
+        Calculator {
+            calc: String::from(""),
+            calc_pos: 0,
+            prev_calcs: vec![],
+            selected_calc: 0,
+            selected_equals: false,
+            variables: BTreeMap::new(),
+            user_functions: BTreeMap::new(),
+            output_base: 10,
+            word_size: None,
+            precision: None,
+            rounding_mode: RoundingMode::HalfEven,
+            rational_mode: None,
+            exact_variables: BTreeMap::new(),
+            angle_mode: AngleUnit::Radians,
+        }
+    }
+
+
+    // built-in functions of one argument, along with an f64 version of each
+    //   that is given to evalexpr for the sanity check
+    const UNARY_FUNCTIONS: [(&'static str, CalcFunction, FloatFunction); 25] = [
+        ("sqrt", bigmath::sqrt, f64::sqrt),
+        ("cbrt", bigmath::cbrt, f64::cbrt),
+        ("abs", bigmath::abs, f64::abs),
+        ("sign", bigmath::sign, Calculator::sign_f64),
+        ("floor", bigmath::floor, f64::floor),
+        ("ceil", bigmath::ceil, f64::ceil),
+        ("round", bigmath::round, f64::round),
+        ("trunc", bigmath::trunc, f64::trunc),
+        ("exp", bigmath::exp, f64::exp),
+        ("ln", bigmath::ln, f64::ln),
+        ("log10", bigmath::log10, f64::log10),
+        ("log2", bigmath::log2, f64::log2),
+        ("sin", bigmath::sin, f64::sin),
+        ("cos", bigmath::cos, f64::cos),
+        ("tan", bigmath::tan, f64::tan),
+        ("asin", bigmath::asin, f64::asin),
+        ("acos", bigmath::acos, f64::acos),
+        ("atan", bigmath::atan, f64::atan),
+        ("sinh", bigmath::sinh, f64::sinh),
+        ("cosh", bigmath::cosh, f64::cosh),
+        ("tanh", bigmath::tanh, f64::tanh),
+        ("isprime", bigmath::isprime, Calculator::isprime_f64),
+        ("factor", bigmath::factor, Calculator::factor_f64),
+        ("degrees", bigmath::degrees, f64::to_degrees),
+        ("radians", bigmath::radians, f64::to_radians),
+    ];
+
+    // the trig functions in UNARY_FUNCTIONS work in radians, and these are
+    //   used instead to work in the angle mode
+    const ANGLE_FUNCTIONS: [(&'static str, CalcAngleFunction); 6] = [
+        ("sin", bigmath::sin_in),
+        ("cos", bigmath::cos_in),
+        ("tan", bigmath::tan_in),
+        ("asin", bigmath::asin_in),
+        ("acos", bigmath::acos_in),
+        ("atan", bigmath::atan_in),
+    ];
+
+    // built-in functions that take a list of comma-separated arguments, along
+    //   with the minimum and maximum (if any) number of arguments allowed
+    // "round" is also in UNARY_FUNCTIONS, which is used when it's given a
+    //   single argument
+    const MULTI_ARG_FUNCTIONS: [(&'static str, usize, Option<usize>, CalcMultiArgFunction, FloatMultiArgFunction); 15] = [
+        ("min", 1, None, bigmath::min, Calculator::min_f64),
+        ("max", 1, None, bigmath::max, Calculator::max_f64),
+        ("sum", 1, None, bigmath::sum, Calculator::sum_f64),
+        ("avg", 1, None, bigmath::avg, Calculator::avg_f64),
+        ("round", 2, Some(2), bigmath::round_digits, Calculator::round_digits_f64),
+        ("atan2", 2, Some(2), bigmath::atan2, Calculator::atan2_f64),
+        ("hypot", 2, Some(2), bigmath::hypot, Calculator::hypot_f64),
+        ("approx", 2, Some(2), bigmath::approx, Calculator::approx_f64),
+        ("nCr", 2, Some(2), bigmath::ncr, Calculator::ncr_f64),
+        ("nPr", 2, Some(2), bigmath::npr, Calculator::npr_f64),
+        ("gcd", 1, None, bigmath::gcd, Calculator::gcd_f64),
+        ("lcm", 1, None, bigmath::lcm, Calculator::lcm_f64),
+        ("modpow", 3, Some(3), bigmath::modpow, Calculator::modpow_f64),
+        ("modinv", 2, Some(2), bigmath::modinv, Calculator::modinv_f64),
+        ("near", 2, Some(3), bigmath::near, Calculator::near_f64),
+    ];
+
+    // functions of integers whose results are integers too, so they can be
+    //   kept exact -- evalexpr's floats can't hold large integers exactly,
+    //   so these aren't compared with it
+    const INTEGER_FUNCTIONS: [&'static str; 8] = ["gcd", "lcm", "isprime", "factor", "modpow", "modinv", "nCr", "nPr"];
+
+    // "if(condition, a, b)" is a built-in function that only calculates the
+    //   one of a and b it gives, so it's evaluated by the tree itself rather
+    //   than being in the tables above
+    const CONDITIONAL_FUNCTION: &'static str = "if";
+
+    // mathematical constants are given to 60 significant digits, and the
+    //   physical constants are the exact values defined by the SI
+    const CONSTANTS: [(&'static str, &'static str); 8] = [
+        ("pi", "3.14159265358979323846264338327950288419716939937510582097494"),
+        ("e", "2.71828182845904523536028747135266249775724709369995957496697"),
+        ("tau", "6.28318530717958647692528676655900576839433879875021164194989"),
+        ("phi", "1.61803398874989484820458683436563811772030917980576286213545"),
+        // speed of light in vacuum, in m/s
+        ("c", "299792458"),
+        // standard acceleration of gravity, in m/s^2
+        ("g", "9.80665"),
+        // Planck constant, in J*s
+        ("h", "6.62607015e-34"),
+        // Avogadro constant, in 1/mol
+        ("NA", "6.02214076e23"),
+    ];
+
+    fn get_constant(name: &str) -> Option<BigDecimal> {
+        for (n, value) in Calculator::CONSTANTS.iter() {
+            if *n == name {
+                return BigDecimal::from_str(value).ok();
+            }
+        }
+        None
+    }
+
+    fn get_constant_names() -> Vec<&'static str> {
+        Calculator::CONSTANTS.iter().map(|c| c.0).collect()
+    }
+
+    fn get_unary_function(name: &str) -> Option<CalcFunction> {
+        for (n, f, _) in Calculator::UNARY_FUNCTIONS.iter() {
+            if *n == name {
+                return Some(*f);
+            }
+        }
+        None
+    }
+
+    fn get_angle_function(name: &str) -> Option<CalcAngleFunction> {
+        for (n, f) in Calculator::ANGLE_FUNCTIONS.iter() {
+            if *n == name {
+                return Some(*f);
+            }
+        }
+        None
+    }
+
+    fn get_multi_arg_function(name: &str, arg_count: usize) -> Option<CalcMultiArgFunction> {
+        for (n, min_args, max_args, f, _) in Calculator::MULTI_ARG_FUNCTIONS.iter() {
+            if *n == name && arg_count >= *min_args &&
+                    (max_args.is_none() || arg_count <= max_args.unwrap()) {
+                return Some(*f);
+            }
+        }
+        None
+    }
+
+    fn is_function_name(name: &str) -> bool {
+        Calculator::UNARY_FUNCTIONS.iter().any(|f| f.0 == name) ||
+            Calculator::MULTI_ARG_FUNCTIONS.iter().any(|f| f.0 == name) ||
+            name == Calculator::CONDITIONAL_FUNCTION
+    }
+
+    fn get_function_names() -> Vec<&'static str> {
+        let mut names: Vec<&'static str> = Calculator::UNARY_FUNCTIONS.iter().map(|f| f.0).collect();
+        for (name, _, _, _, _) in Calculator::MULTI_ARG_FUNCTIONS.iter() {
+            if !names.contains(name) {
+                names.push(name);
+            }
+        }
+        names
+    }
+
+    // f64::signum() returns 1 for zero
+    fn sign_f64(x: f64) -> f64 {
+        if x == 0.0 {
+            return 0.0;
+        }
+        x.signum()
+    }
+
+    fn min_f64(args: &[f64]) -> f64 {
+        args.iter().cloned().fold(f64::INFINITY, f64::min)
+    }
+
+    fn max_f64(args: &[f64]) -> f64 {
+        args.iter().cloned().fold(f64::NEG_INFINITY, f64::max)
+    }
+
+    fn sum_f64(args: &[f64]) -> f64 {
+        args.iter().sum()
+    }
+
+    fn avg_f64(args: &[f64]) -> f64 {
+        Calculator::sum_f64(args) / args.len() as f64
+    }
+
+    fn round_digits_f64(args: &[f64]) -> f64 {
+        let scale = 10f64.powf(args[1]);
+        (args[0] * scale).round() / scale
+    }
+
+    fn atan2_f64(args: &[f64]) -> f64 {
+        args[0].atan2(args[1])
+    }
+
+    fn hypot_f64(args: &[f64]) -> f64 {
+        args[0].hypot(args[1])
+    }
+
+    // only used for the sanity check, so this skips checking its arguments
+    fn npr_f64(args: &[f64]) -> f64 {
+        let mut product = 1.0;
+        let mut i = 0.0;
+        while i < args[1] {
+            product *= args[0] - i;
+            i += 1.0;
+        }
+        product
+    }
+
+    fn ncr_f64(args: &[f64]) -> f64 {
+        let (n, k) = (args[0], args[1].min(args[0] - args[1]));
+        if k < 0.0 {
+            return 0.0;
+        }
+        let mut product = 1.0;
+        let mut i = 1.0;
+        while i <= k {
+            product = product * (n - k + i) / i;
+            i += 1.0;
+        }
+        product
+    }
+
+    // for functions with no simple f64 version, the floats are given to the
+    //   BigDecimal one
+    fn apply_as_bigdecimal_f64(args: &[f64], function: CalcMultiArgFunction) -> f64 {
+        let mut decimal_args = vec![];
+        for a in args.iter() {
+            match BigDecimal::from_str(&a.to_string()) {
+                Ok(x) => { decimal_args.push(x); },
+                Err(_) => { return f64::NAN; }
+            }
+        }
+        match function(&decimal_args) {
+            Ok(x) => x.to_f64().unwrap_or(f64::NAN),
+            Err(_) => f64::NAN
+        }
+    }
+
+    fn near_f64(args: &[f64]) -> f64 {
+        Calculator::apply_as_bigdecimal_f64(args, bigmath::near)
+    }
+
+    fn approx_f64(args: &[f64]) -> f64 {
+        Calculator::apply_as_bigdecimal_f64(args, bigmath::approx)
+    }
+
+    fn gcd_f64(args: &[f64]) -> f64 {
+        Calculator::apply_as_bigdecimal_f64(args, bigmath::gcd)
+    }
+
+    fn lcm_f64(args: &[f64]) -> f64 {
+        Calculator::apply_as_bigdecimal_f64(args, bigmath::lcm)
+    }
+
+    fn modpow_f64(args: &[f64]) -> f64 {
+        Calculator::apply_as_bigdecimal_f64(args, bigmath::modpow)
+    }
+
+    fn modinv_f64(args: &[f64]) -> f64 {
+        Calculator::apply_as_bigdecimal_f64(args, bigmath::modinv)
+    }
+
+    fn isprime_f64(x: f64) -> f64 {
+        Calculator::apply_as_bigdecimal_f64(&[x], |args| bigmath::isprime(&args[0]))
+    }
+
+    fn factor_f64(x: f64) -> f64 {
+        Calculator::apply_as_bigdecimal_f64(&[x], |args| bigmath::factor(&args[0]))
+    }
 
     fn append_key_to_calc(&mut self, k: &CalcKey) {
         self.selected_calc = 0;
         self.selected_equals = false;
         match k {
             CalcKey::Key(x) => {
-                self.calc.insert((self.calc_pos).into(), *x);
+                self.calc.insert(self.get_calc_byte_pos(self.calc_pos.into()), *x);
                 self.calc_pos += 1;
             },
             CalcKey::Delete => {
@@ -341,7 +1120,7 @@ impl Calculator {
                 }
                 let mut delete_pos: usize = (self.calc_pos).into();
                 delete_pos -= 1;
-                self.calc.remove(delete_pos);
+                self.calc.remove(self.get_calc_byte_pos(delete_pos));
                 if self.calc_pos > 0 {
                     self.calc_pos -= 1;
                 }
@@ -349,58 +1128,70 @@ impl Calculator {
         }
     }
 
+    // the cursor position counts chars, and a char like "°" takes more than
+    //   one byte of the calc
+    fn get_calc_byte_pos(&self, char_pos: usize) -> usize {
+        match self.calc.char_indices().nth(char_pos) {
+            Some((i, _)) => i,
+            None => self.calc.len()
+        }
+    }
+
+    fn get_calc_char_count(&self) -> usize {
+        self.calc.chars().count()
+    }
+
     fn perform_calculation(&mut self) {
         let calc_copy = self.calc.clone();
-        let js_result = Calculator::perform_calc_js_eval(&self.calc);
-        let tree_result = Calculator::perform_calc_eval(&self.calc);
-        let js_result_float = match js_result {
-            CalcResult::Float(f) => Ok(f),
-            CalcResult::Integer(i) => {
-                match i.to_string().parse::<f64>() {
-                    Ok(x) => Ok(x),
-                    Err(_) => Err("unable to parse int to float".to_string())
-                }
+        // the output base only affects how the result is shown, so it's
+        //   read back from the calc when the history is shown
+        let calc_no_base = match Calculator::split_output_base(&calc_copy) {
+            // "in hex" on its own sets the output base for all results
+            Ok((expression, Some(base))) if expression.is_empty() => {
+                self.output_base = base;
+                self.calc.clear();
+                self.calc_pos = 0;
+                return;
             },
-            // TODO: allow the original error message to be passed through here
-            //CalcResult::Error(e) => Err(e)
-            CalcResult::Error(_) => Err("error".to_string())
+            Ok((expression, _)) => expression,
+            Err(m) => {
+                self.prev_calcs.push((calc_copy, CalcResult::Error(m)));
+                self.calc.clear();
+                self.calc_pos = 0;
+                return;
+            }
         };
-        let tree_result_float = match tree_result {
-            CalcResult::Float(f) => Ok(f),
-            CalcResult::Integer(i) => {
-                match i.to_string().parse::<f64>() {
-                    Ok(x) => Ok(x),
-                    Err(_) => Err("unable to parse int to float".to_string())
-                }
+        // like the output base, the fraction format is read back from the calc
+        //   when the calculation is done
+        let calc_no_base = match Calculator::split_fraction_format(&calc_no_base) {
+            // "as fraction" on its own calculates all results exactly, while
+            //   "as decimal" on its own goes back to the usual decimals
+            (expression, Some(format)) if expression.is_empty() => {
+                self.rational_mode = match format {
+                    CalcFractionFormat::Decimal => None,
+                    _ => Some(format)
+                };
+                self.calc.clear();
+                self.calc_pos = 0;
+                return;
             },
-            // TODO: allow the original error message to be passed through here
-            //CalcResult::Error(e) => Err(e)
-            CalcResult::Error(_) => Err("error".to_string())
+            (expression, _) => expression
         };
-
-        let mut sanity_check_compare_success = false;
-        if js_result_float.is_err() && tree_result_float.is_err() {
-            self.prev_calcs.push(("calculations using bigdecimal tree post order, and evalexpr, could not be cast to floats for sanity check comparison".to_string(), CalcResult::Error("error".to_string())));
-        } else if js_result_float.is_ok() && tree_result_float.is_err() {
-            self.prev_calcs.push(("calculation using bigdecimal tree post order could not be cast to float for sanity check comparison".to_string(), CalcResult::Error("error".to_string())));
-        } else if js_result_float.is_err() && tree_result_float.is_ok() {
-            self.prev_calcs.push(("calculation using evalexpr could not be cast to float for sanity check comparison".to_string(), CalcResult::Error("error".to_string())));
-        } else if js_result_float.is_ok() && tree_result_float.is_ok() {
-            if Calculator::is_within_acceptable_range(js_result_float.unwrap(), tree_result_float.unwrap()) {
-                sanity_check_compare_success = true;
-            } else {
-                self.prev_calcs.push(("calculations using bigdecimal tree post order, and evalexpr, do not match".to_string(), CalcResult::Error("error".to_string())));
+        // "digits 6", "places 2" and "round half-up" (or similar) change how
+        //   all results are rounded
+        if let Some(setting) = Calculator::parse_rounding_setting(&calc_no_base) {
+            if let Err(m) = self.apply_rounding_setting(setting) {
+                self.prev_calcs.push((calc_copy, CalcResult::Error(m)));
             }
+            self.calc.clear();
+            self.calc_pos = 0;
+            return;
         }
-        if sanity_check_compare_success {
-            self.prev_calcs.push((calc_copy, tree_result));
-        } else {
-            let error_message = self.prev_calcs.pop();
-            self.prev_calcs.push((format!("evalexpr: {}", calc_copy), js_result));
-            self.prev_calcs.push((format!("bigdecimal tree: {}", calc_copy), tree_result));
-            if error_message.is_some() {
-                self.prev_calcs.push(error_message.unwrap());
-            }
+        match Calculator::parse_assignment(&calc_no_base) {
+            Ok(Some((CalcAssignment::Variable(name), expression))) => self.perform_assignment(calc_copy.clone(), &name, &expression),
+            Ok(Some((CalcAssignment::Function(name, params), expression))) => self.define_function(calc_copy.clone(), &name, params, &expression),
+            Ok(None) => self.push_calculation(calc_copy.clone(), &calc_no_base),
+            Err(m) => self.prev_calcs.push((calc_copy, CalcResult::Error(m)))
         }
 
         while self.prev_calcs.len() > 1000 {
@@ -410,13 +1201,477 @@ impl Calculator {
         self.calc_pos = 0;
     }
 
-    fn perform_calc_js_eval(calc: &str) -> CalcResult {
-        let calc_clean = calc.replace("$", "").replace(",", "").replace(" ","");
-        // replace all integer values with "integer value".0 below, to force
+    // the calc is shown in the history as typed, while only the expression
+    //   part of it (without any "name =") is evaluated
+    fn push_calculation(&mut self, calc_copy: String, expression: &str) {
+        // evalexpr knows nothing of word sizes, so there's nothing to compare
+        //   programmer mode results against
+        if let Some(word_size) = self.word_size {
+            let result = self.perform_calc_eval_in_word(expression, word_size);
+            self.prev_calcs.push((calc_copy, result));
+        } else if let Some(format) = self.get_fraction_format(&calc_copy) {
+            let is_requested = Calculator::get_requested_fraction_format(&calc_copy).is_some();
+            let result = self.perform_calc_eval_exact(expression, format, is_requested);
+            self.prev_calcs.push((calc_copy, result));
+        // evalexpr can't evaluate everything the tree can, so the two are only
+        //   compared when the calculation sticks to what both of them support
+        } else if self.is_evalexpr_comparable(expression) {
+            self.push_sanity_checked_calculation(calc_copy, expression);
+        } else {
+            let result = self.perform_calc_eval(expression);
+            self.prev_calcs.push((calc_copy, result));
+        }
+    }
+
+    // "name = expression" assigns a variable, and "name =" deletes one
+    // "name(x, y) = expression" defines a function, and "name(x, y) =" deletes one
+    // returns None if the calc is not an assignment
+    fn parse_assignment(calc: &str) -> Result<Option<(CalcAssignment, String)>, String> {
+        let (target, expression) = match Calculator::find_assignment_sign(calc) {
+            Some(i) => (&calc[..i], &calc[i + 1..]),
+            None => { return Ok(None); }
+        };
+        let target = target.trim();
+        if Calculator::find_assignment_sign(expression).is_some() {
+            return Err("only one \"=\" is allowed, to assign a variable or define a function".to_string());
+        }
+        let assignment = match target.split_once('(') {
+            Some((name, params)) => {
+                let name = name.trim();
+                if let Err(m) = Calculator::check_assignable_name(name) {
+                    return Err(format!("cannot define [{}]: {}", name, m));
+                }
+                let params = match params.strip_suffix(')') {
+                    Some(p) => p,
+                    None => { return Err(format!("cannot define [{}]: expected a \")\" after its parameters", name)); }
+                };
+                let mut param_names: Vec<String> = vec![];
+                for param in params.split(',') {
+                    let param = param.trim();
+                    if let Err(m) = Calculator::check_assignable_name(param) {
+                        return Err(format!("cannot use [{}] as a parameter of [{}]: {}", param, name, m));
+                    }
+                    if param_names.iter().any(|p| p == param) {
+                        return Err(format!("cannot use [{}] as a parameter of [{}] more than once", param, name));
+                    }
+                    param_names.push(param.to_string());
+                }
+                CalcAssignment::Function(name.to_string(), param_names)
+            },
+            None => {
+                if let Err(m) = Calculator::check_assignable_name(target) {
+                    return Err(format!("cannot assign to [{}]: {}", target, m));
+                }
+                // function parameters, however, may hide a constant (like "h")
+                if Calculator::get_constant(target).is_some() {
+                    return Err(format!("cannot assign to [{}]: it is the name of a constant", target));
+                }
+                if Calculator::get_angle_unit(target).is_some() {
+                    return Err(format!("cannot assign to [{}]: it is the name of an angle unit", target));
+                }
+                CalcAssignment::Variable(target.to_string())
+            }
+        };
+        Ok(Some((assignment, expression.trim().to_string())))
+    }
+
+    // the position of an "=" that is not part of a comparison, like the
+    //   first one in "same = x == y"
+    fn find_assignment_sign(calc: &str) -> Option<usize> {
+        let chars: Vec<char> = calc.chars().collect();
+        let mut pos = 0;
+        for (i, c) in chars.iter().enumerate() {
+            let is_comparison = (i > 0 && "<>=!".contains(chars[i - 1])) ||
+                chars.get(i + 1) == Some(&'=');
+            if *c == '=' && !is_comparison {
+                return Some(pos);
+            }
+            pos += c.len_utf8();
+        }
+        None
+    }
+
+    // variables, user-defined functions, and their parameters share these rules
+    fn check_assignable_name(name: &str) -> Result<(), String> {
+        if name.starts_with(CalcParseToken::HISTORY_REFERENCE_CHAR) || name == Calculator::LAST_RESULT_NAME {
+            return Err("it refers to a previous result".to_string());
+        }
+        if CalcParseToken::get_token_matching_str(name) != Some(CalcParseToken::Identifier) {
+            return Err("a name must start with a letter or \"_\"".to_string());
+        }
+        if Calculator::is_function_name(name) {
+            return Err("it is the name of a built-in function".to_string());
+        }
+        Ok(())
+    }
+
+    fn perform_assignment(&mut self, calc_copy: String, name: &str, expression: &str) {
+        if expression.is_empty() {
+            self.exact_variables.remove(name);
+            if self.variables.remove(name).is_none() {
+                self.prev_calcs.push((calc_copy, CalcResult::Error(format!("cannot delete unknown variable [{}]", name))));
+            }
+            return;
+        }
+        // evaluate before assigning, so that the expression sees the
+        //   variable's previous value, like "count = count + 1"
+        let previous_len = self.prev_calcs.len();
+        self.push_calculation(calc_copy, expression);
+        let result = match self.prev_calcs.get(previous_len) {
+            Some((_, result)) => result,
+            None => { return; }
+        };
+        // the variable holds the value that is shown: exactly, when it's
+        //   shown as a fraction or repeating decimal, so "x = 1/3" then "x*3"
+        //   is 1, and otherwise rounded like the shown result
+        let value = match Calculator::history_result_to_bigdecimal(result) {
+            Ok(x) => x,
+            Err(_) => { return; }
+        };
+        match result {
+            CalcResult::Fraction(x, _) => {
+                let exact_value = x.clone();
+                self.exact_variables.insert(name.to_string(), exact_value);
+            },
+            _ => { self.exact_variables.remove(name); }
+        }
+        let rounded = self.round_to_precision(&value);
+        self.variables.insert(name.to_string(), rounded);
+    }
+
+    fn define_function(&mut self, calc_copy: String, name: &str, params: Vec<String>, expression: &str) {
+        if expression.is_empty() {
+            if self.user_functions.remove(name).is_none() {
+                self.prev_calcs.push((calc_copy, CalcResult::Error(format!("cannot delete unknown function [{}]", name))));
+            }
+            return;
+        }
+        match Calculator::build_calc_eval_tree(expression) {
+            Ok(tree) => {
+                self.user_functions.insert(name.to_string(), CalcUserFunction {
+                    params,
+                    body: expression.to_string(),
+                    tree
+                });
+            },
+            Err(m) => self.prev_calcs.push((calc_copy, CalcResult::Error(m)))
+        }
+    }
+
+    fn format_user_functions(&self) -> String {
+        let mut formatted: Vec<String> = vec![];
+        for (name, function) in self.user_functions.iter() {
+            formatted.push(format!("{}({}) = {}", name, function.params.join(", "), function.body));
+        }
+        formatted.join("   ")
+    }
+
+    const LAST_RESULT_NAME: &'static str = "ans";
+
+    fn history_result_to_bigdecimal(result: &CalcResult) -> Result<BigDecimal, String> {
+        let value = match result {
+            CalcResult::Word(value, _, _) | CalcResult::Factors(value, _) => BigDecimal::from(value.clone()),
+            CalcResult::Decimal(x) => x.clone(),
+            CalcResult::Fraction(x, _) => x.to_bigdecimal(RoundingMode::HalfEven),
+            CalcResult::Boolean(b) => Calculator::boolean_to_bigdecimal(*b),
+            CalcResult::Float(f) => match BigDecimal::from_str(&f.to_string()) {
+                Ok(x) => x,
+                Err(_) => { return Err(format!("unable to parse previous result [{}] into a BigDecimal", f)); }
+            },
+            CalcResult::Error(_) => { return Err("the previous result is an error".to_string()); }
+        };
+        Ok(value)
+    }
+
+    // fractions are kept exact, and decimals are exactly a fraction anyway
+    fn history_result_to_rational(result: &CalcResult) -> Result<Rational, String> {
+        if let CalcResult::Fraction(x, _) = result {
+            return Ok(x.clone());
+        }
+        match Calculator::history_result_to_bigdecimal(result) {
+            Ok(x) => Ok(Rational::from_bigdecimal(&x)),
+            Err(m) => Err(m)
+        }
+    }
+
+    // "ans" is the most recent result that is not an error
+    // the result is given as a BigDecimal or a Rational by the convert function
+    fn get_last_result<T>(&self, convert: fn(&CalcResult) -> Result<T, String>) -> Result<T, String> {
+        for (_, result) in self.prev_calcs.iter().rev() {
+            if let Ok(x) = convert(result) {
+                return Ok(x);
+            }
+        }
+        Err(format!("[{}] has no value: there are no previous results", Calculator::LAST_RESULT_NAME))
+    }
+
+    // "#1" is the most recent history entry, "#2" the one before it, and so on,
+    //   matching the order they are listed on screen
+    fn get_history_result<T>(&self, reference: &str, convert: fn(&CalcResult) -> Result<T, String>) -> Result<T, String> {
+        let n = match reference[1..].parse::<usize>() {
+            Ok(n) => n,
+            Err(_) => { return Err(format!("invalid previous result reference [{}]", reference)); }
+        };
+        if n == 0 || n > self.prev_calcs.len() {
+            return Err(format!("there is no previous result [{}]", reference));
+        }
+        match convert(&self.prev_calcs[self.prev_calcs.len() - n].1) {
+            Ok(x) => Ok(x),
+            Err(_) => Err(format!("previous result [{}] is an error", reference))
+        }
+    }
+
+    fn format_variables(&self) -> String {
+        let mut formatted: Vec<String> = vec![];
+        for (name, value) in self.variables.iter() {
+            formatted.push(format!("{} = {}", name, value));
+        }
+        formatted.join("   ")
+    }
+
+    fn push_sanity_checked_calculation(&mut self, calc_copy: String, expression: &str) {
+        let value = match self.evaluate_calc_value(expression) {
+            Ok(x) => x,
+            // an invalid calc, like "sqrt 2", is reported as the tree sees it,
+            //   since evalexpr has nothing to add
+            Err(m) => {
+                self.prev_calcs.push((calc_copy, CalcResult::Error(m)));
+                return;
+            }
+        };
+        let tree_result = CalcResult::Decimal(value.to_known_digits(self.rounding_mode));
+        let js_result = self.perform_calc_js_eval(expression);
+        let js_result_float = match js_result {
+            CalcResult::Float(f) => Ok(f),
+            CalcResult::Word(ref value, _, _) | CalcResult::Factors(ref value, _) => {
+                match value.to_string().parse::<f64>() {
+                    Ok(x) => Ok(x),
+                    Err(_) => Err("unable to parse int to float".to_string())
+                }
+            },
+            CalcResult::Decimal(ref x) => {
+                match x.to_string().parse::<f64>() {
+                    Ok(f) => Ok(f),
+                    Err(_) => Err("unable to parse decimal to float".to_string())
+                }
+            },
+            CalcResult::Fraction(ref x, _) => {
+                match x.to_bigdecimal(RoundingMode::HalfEven).to_string().parse::<f64>() {
+                    Ok(f) => Ok(f),
+                    Err(_) => Err("unable to parse fraction to float".to_string())
+                }
+            },
+            CalcResult::Boolean(b) => Ok(if b { 1.0 } else { 0.0 }),
+            // TODO: allow the original error message to be passed through here
+            //CalcResult::Error(e) => Err(e)
+            CalcResult::Error(_) => Err("error".to_string())
+        };
+        let tree_result_float = match tree_result {
+            CalcResult::Float(f) => Ok(f),
+            CalcResult::Word(ref value, _, _) | CalcResult::Factors(ref value, _) => {
+                match value.to_string().parse::<f64>() {
+                    Ok(x) => Ok(x),
+                    Err(_) => Err("unable to parse int to float".to_string())
+                }
+            },
+            CalcResult::Decimal(ref x) => {
+                match x.to_string().parse::<f64>() {
+                    Ok(f) => Ok(f),
+                    Err(_) => Err("unable to parse decimal to float".to_string())
+                }
+            },
+            CalcResult::Fraction(ref x, _) => {
+                match x.to_bigdecimal(RoundingMode::HalfEven).to_string().parse::<f64>() {
+                    Ok(f) => Ok(f),
+                    Err(_) => Err("unable to parse fraction to float".to_string())
+                }
+            },
+            CalcResult::Boolean(b) => Ok(if b { 1.0 } else { 0.0 }),
+            // TODO: allow the original error message to be passed through here
+            //CalcResult::Error(e) => Err(e)
+            CalcResult::Error(_) => Err("error".to_string())
+        };
+
+        let mut sanity_check_compare_success = false;
+        // a float can't hold a result this large, so there is nothing to
+        //   compare the exact result against
+        if let Ok(f) = tree_result_float {
+            if f.is_infinite() {
+                self.prev_calcs.push((calc_copy, self.finish_result(expression, value)));
+                return;
+            }
+        }
+        if js_result_float.is_err() && tree_result_float.is_err() {
+            self.prev_calcs.push(("calculations using bigdecimal tree post order, and evalexpr, could not be cast to floats for sanity check comparison".to_string(), CalcResult::Error("error".to_string())));
+        } else if js_result_float.is_ok() && tree_result_float.is_err() {
+            self.prev_calcs.push(("calculation using bigdecimal tree post order could not be cast to float for sanity check comparison".to_string(), CalcResult::Error("error".to_string())));
+        } else if js_result_float.is_err() && tree_result_float.is_ok() {
+            self.prev_calcs.push(("calculation using evalexpr could not be cast to float for sanity check comparison".to_string(), CalcResult::Error("error".to_string())));
+        } else if js_result_float.is_ok() && tree_result_float.is_ok() {
+            if Calculator::is_within_acceptable_range(js_result_float.unwrap(), tree_result_float.unwrap()) {
+                sanity_check_compare_success = true;
+            } else {
+                self.prev_calcs.push(("calculations using bigdecimal tree post order, and evalexpr, do not match".to_string(), CalcResult::Error("error".to_string())));
+            }
+        }
+        // results are only rounded after the comparison, since a float can't be
+        //   expected to match a result rounded to a few digits
+        if sanity_check_compare_success {
+            self.prev_calcs.push((calc_copy, self.finish_result(expression, value)));
+        } else {
+            let error_message = self.prev_calcs.pop();
+            self.prev_calcs.push((format!("evalexpr: {}", calc_copy), js_result));
+            self.prev_calcs.push((format!("bigdecimal tree: {}", calc_copy), tree_result));
+            if let Some(m) = error_message {
+                self.prev_calcs.push(m);
+            }
+        }
+    }
+
+    // evalexpr has no "//" operator, its "%" keeps the sign of the
+    //   dividend rather than the divisor, and it has no percent sign --
+    // nor any bitwise operators, factorials, or degree signs
+    // its comparisons and logical operators give booleans, not numbers
+    const EVALEXPR_UNSUPPORTED_OPERATORS: [&'static str; 21] = [
+        "//", "mod", "%", "of", "&", "|", "xor", "~", "<<", ">>", "!", "°",
+        "<", "<=", ">", ">=", "==", "!=", "and", "or", "not"
+    ];
+
+    fn is_evalexpr_comparable(&self, calc: &str) -> bool {
+        let tokens = match Calculator::parse_calc_to_tokens(calc) {
+            Ok(t) => t,
+            Err(_) => { return false; }
+        };
+        for token in tokens.iter() {
+            if Calculator::EVALEXPR_UNSUPPORTED_OPERATORS.contains(&token.as_str()) {
+                return false;
+            }
+            // the tree refuses a value like "1e99999999", which evalexpr
+            //   would need to have written out in full
+            if CalcParseToken::get_token_matching_str(token) == Some(CalcParseToken::Value) &&
+                    Calculator::parse_value(token).is_err() {
+                return false;
+            }
+            // "#2" is not a valid evalexpr identifier
+            if CalcParseToken::is_history_reference(token) {
+                return false;
+            }
+            // nor is "0.(3)" a value
+            if CalcParseToken::parse_repeating_decimal(token).is_some() {
+                return false;
+            }
+            // evalexpr doesn't know about functions defined in the calc
+            if self.user_functions.contains_key(token) {
+                return false;
+            }
+            if Calculator::INTEGER_FUNCTIONS.contains(&token.as_str()) {
+                return false;
+            }
+            // an "if" isn't one of evalexpr's functions, and a float result
+            //   is only near another one to within about 1e-15
+            if token == Calculator::CONDITIONAL_FUNCTION || token == "near" {
+                return false;
+            }
+            // evalexpr has no angle units, and its trig functions only work
+            //   in radians
+            if Calculator::get_angle_unit(token).is_some() {
+                return false;
+            }
+            if self.angle_mode != AngleUnit::Radians && (Calculator::get_angle_function(token).is_some() || token == "atan2") {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn perform_calc_js_eval(&self, calc: &str) -> CalcResult {
+        // the tokenizer drops "$" signs, spaces, and thousands separators
+        //   while keeping commas between function arguments
+        // evalexpr can't read a negative exponent, like "1.5e-9", so values
+        //   in scientific notation are written out in full
+        // evalexpr has no prefix "+", which doesn't change a value anyway
+        let mut tokens = match Calculator::parse_calc_to_tokens(calc) {
+            Ok(t) => Calculator::drop_prefix_plus_signs(t),
+            Err(m) => { return CalcResult::Error(m); }
+        };
+        // evalexpr also can't read prefixed literals, like "0xFF", so they
+        //   are written in decimal
+        for token in tokens.iter_mut() {
+            if let Some(x) = CalcParseToken::parse_base_literal(token) {
+                *token = x.to_string();
+                continue;
+            }
+            if CalcParseToken::get_token_matching_str(token) == Some(CalcParseToken::Value) &&
+                    token.contains(['e', 'E']) {
+                match BigDecimal::from_str(token) {
+                    // writing out "1e99999999" would take minutes, or run out of memory
+                    Ok(x) if x.as_bigint_and_exponent().1.abs() > Calculator::MAX_LITERAL_EXPONENT => {
+                        return CalcResult::Error(format!("the exponent of value [{}] is too large", token));
+                    },
+                    Ok(x) => { *token = x.to_string(); },
+                    Err(_) => { return CalcResult::Error(format!("unable to parse value [{}] for evalexpr", token)); }
+                }
+            }
+        }
+        let calc_clean = tokens.concat();
+        // replace all integer values with "integer value".0 below, to force
         //   evalexp to use floating point math -- otherwise all-integer input
         //   calculations are truncated to an integer result ("5/3 = 1"!)
         let calc_float = Calculator::convert_integers_to_decimals(&calc_clean);
-        match eval_float(&calc_float) {
+        let calc_float = Calculator::group_exponents_for_evalexpr(&calc_float);
+        let mut context = HashMapContext::new();
+        for name in Calculator::get_function_names().iter() {
+            let unary_function = Calculator::UNARY_FUNCTIONS.iter()
+                .find(|f| f.0 == *name).map(|f| f.2);
+            let multi_arg_function = Calculator::MULTI_ARG_FUNCTIONS.iter()
+                .find(|f| f.0 == *name).map(|f| f.4);
+            // evalexpr passes multiple arguments as a tuple
+            let function = Function::new(Box::new(move |argument| {
+                let args = match argument {
+                    Value::Tuple(t) => {
+                        let mut args = vec![];
+                        for a in t.iter() {
+                            args.push(a.as_number()?);
+                        }
+                        args
+                    },
+                    a => vec![a.as_number()?]
+                };
+                match (unary_function, multi_arg_function) {
+                    (Some(f), _) if args.len() == 1 => Ok(Value::Float(f(args[0]))),
+                    (_, Some(f)) => Ok(Value::Float(f(&args))),
+                    _ => Err(EvalexprError::WrongFunctionArgumentAmount { expected: 1, actual: args.len() })
+                }
+            }));
+            if context.set_function(name.to_string(), function).is_err() {
+                return CalcResult::Error(format!("unable to give function [{}] to evalexpr", name));
+            }
+        }
+        for (name, value) in Calculator::CONSTANTS.iter() {
+            let value_float = match value.parse::<f64>() {
+                Ok(f) => f,
+                Err(_) => { return CalcResult::Error(format!("unable to convert constant [{}] to a float", name)); }
+            };
+            if context.set_value(name.to_string(), Value::Float(value_float)).is_err() {
+                return CalcResult::Error(format!("unable to give constant [{}] to evalexpr", name));
+            }
+        }
+        for (name, value) in self.variables.iter() {
+            let value_float = match value.to_f64() {
+                Some(f) => f,
+                None => { return CalcResult::Error(format!("unable to convert variable [{}] to a float", name)); }
+            };
+            if context.set_value(name.to_string(), Value::Float(value_float)).is_err() {
+                return CalcResult::Error(format!("unable to give variable [{}] to evalexpr", name));
+            }
+        }
+        if let Ok(value) = self.get_last_result(Calculator::history_result_to_bigdecimal) {
+            let value_float = value.to_f64().unwrap_or(f64::NAN);
+            if context.set_value(Calculator::LAST_RESULT_NAME.to_string(), Value::Float(value_float)).is_err() {
+                return CalcResult::Error(format!("unable to give [{}] to evalexpr", Calculator::LAST_RESULT_NAME));
+            }
+        }
+        match eval_float_with_context(&calc_float, &context) {
             EvalexprResult::Ok(value) => CalcResult::Float(value),
             //_ => CalcResult::Error(String::from("error"))
             EvalexprResult::Err(error) => CalcResult::Error(format!("calc:[{}], error:[{:?}]", calc_float, error))
@@ -427,25 +1682,52 @@ impl Calculator {
     //   regular expressions
     fn convert_integers_to_decimals(calc: &str) -> String {
         let digits = "0123456789";
+        let chars: Vec<char> = calc.chars().collect();
         let mut within_number = false;
         let mut within_int = false;
+        // digits within an identifier, like "log10", are not numbers
+        let mut within_identifier = false;
+        // the exponent of a value in scientific notation is kept as is, so
+        //   "2e-3" becomes "2.0e-3"
+        let mut within_exponent = false;
         let mut result = String::new();
-        for c in calc.chars() {
-            if digits.contains(c) {
+        for (i, c) in chars.iter().copied().enumerate() {
+            if within_exponent {
+                if digits.contains(c) ||
+                        ((c == '-' || c == '+') && (result.ends_with('e') || result.ends_with('E'))) {
+                    result.push(c);
+                    continue;
+                }
+                within_exponent = false;
+                within_number = false;
+            } else if within_number && (c == 'e' || c == 'E') && Calculator::is_exponent_start(&chars[i + 1..]) {
+                if within_int {
+                    result.push('.');
+                    result.push('0');
+                }
+                within_int = false;
+                within_exponent = true;
+                result.push(c);
+                continue;
+            }
+            if CalcParseToken::IDENTIFIER_CHARS.contains(c) {
+                within_identifier = true;
+            } else if !digits.contains(c) {
+                within_identifier = false;
+            }
+            if digits.contains(c) && !within_identifier {
                 if !within_number {
                     within_number = true;
                     within_int = true;
                 }
             } else {
-                if within_int {
-                    if c != '.' {
-                        within_number = false;
-                        result.push('.');
-                        result.push('0');
-                    }
-                } else if c == '.' {
-                    within_number = true;
+                if within_int && c != '.' {
+                    result.push('.');
+                    result.push('0');
                 }
+                // a "." continues a number (or starts one, like ".5") and anything
+                //   else ends it
+                within_number = c == '.';
                 within_int = false;
             }
             result.push(c);
@@ -455,13 +1737,115 @@ impl Calculator {
             result.push('.');
             result.push('0');
         }
-        return result;
+        result
+    }
+
+    // evalexpr evaluates "^" left to right, and binds a leading "-" on a
+    //   number less tightly than "^", so wrap both sides of each "^" in
+    //   parens to have it agree with the tree: "2^3^2" becomes "(2)^((3)^(2))"
+    //   and "-2^2" becomes "-(2)^(2)"
+    // a function call is also wrapped, in case evalexpr would apply the
+    //   function to the whole power: "sqrt(4)^2" becomes "(sqrt(4))^(2)"
+    fn group_exponents_for_evalexpr(calc: &str) -> String {
+        // the calc was already tokenized before getting here
+        let tokens = Calculator::parse_calc_to_tokens(calc).unwrap_or_default();
+        let power_precedence = CalcParseToken::get_operator_precedence("^").unwrap();
+        // number of parens to open/close immediately before each token
+        let mut opens = vec![0; tokens.len() + 1];
+        let mut closes = vec![0; tokens.len() + 1];
+        for (i, token) in tokens.iter().enumerate() {
+            if token != "^" {
+                continue;
+            }
+            // the left hand side is a value, or a paren group along with the
+            //   function name in front of it, if any
+            if i > 0 {
+                let mut start = i - 1;
+                if tokens[start] == ")" {
+                    let mut depth = 0;
+                    loop {
+                        if tokens[start] == ")" {
+                            depth += 1;
+                        } else if tokens[start] == "(" {
+                            depth -= 1;
+                        }
+                        if depth == 0 || start == 0 {
+                            break;
+                        }
+                        start -= 1;
+                    }
+                    if start > 0 && CalcParseToken::get_token_matching_str(&tokens[start - 1]) == Some(CalcParseToken::Identifier) {
+                        start -= 1;
+                    }
+                }
+                opens[start] += 1;
+                closes[i] += 1;
+            }
+            // the right hand side extends until the end of the enclosing
+            //   parens or an operator that binds less tightly than "^"
+            let mut depth = 0;
+            let mut end = i + 1;
+            while end < tokens.len() {
+                let t = &tokens[end];
+                if t == "(" {
+                    depth += 1;
+                } else if t == ")" {
+                    if depth == 0 {
+                        break;
+                    }
+                    depth -= 1;
+                } else if depth == 0 {
+                    match CalcParseToken::get_operator_precedence(t) {
+                        Some(p) if p < power_precedence => { break; },
+                        _ => ()
+                    }
+                }
+                end += 1;
+            }
+            opens[i + 1] += 1;
+            closes[end] += 1;
+        }
+        let mut result = String::new();
+        for i in 0..=tokens.len() {
+            for _ in 0..closes[i] {
+                result.push(')');
+            }
+            for _ in 0..opens[i] {
+                result.push('(');
+            }
+            if i < tokens.len() {
+                result.push_str(&tokens[i]);
+            }
+        }
+        result
+    }
+
+    fn drop_prefix_plus_signs(tokens: Vec<String>) -> Vec<String> {
+        let mut result: Vec<String> = vec![];
+        for token in tokens.into_iter() {
+            let follows_operand = matches!(result.last().and_then(|t| CalcParseToken::get_token_matching_str(t)),
+                Some(CalcParseToken::Value) | Some(CalcParseToken::Identifier) |
+                Some(CalcParseToken::CloseParen) | Some(CalcParseToken::Postfix));
+            if token == "+" && !follows_operand {
+                continue;
+            }
+            result.push(token);
+        }
+        result
     }
 
+    // a float can only get within about 1e-16 of zero where the tree gets
+    //   much closer, like sin(pi), so the ratio means nothing there
+    const ACCEPTABLE_DIFFERENCE_NEAR_ZERO: f64 = 1e-12;
+
     fn is_within_acceptable_range(a_orig: f64, b_orig: f64) -> bool {
         let mut a = a_orig;
         let mut b = b_orig;
 
+        if (a - b).abs() < Calculator::ACCEPTABLE_DIFFERENCE_NEAR_ZERO {
+            return true;
+        }
+
         // handle cases where one or both is zero -- adding a small value to
         //   each won't really affect the ratio between them
         if a == 0.0 || b == 0.0 {
@@ -478,93 +1862,710 @@ impl Calculator {
         ratio > 0.9999
     }
 
-    fn perform_calc_eval(calc: &str) -> CalcResult {
-        match Calculator::evaluate_calc(calc) {
-            Ok(string_value) => {
-                if string_value.contains(".") {
-                    match string_value.parse::<f64>() {
-                        Ok(f) => CalcResult::Float(f),
-                        Err(_) => CalcResult::Error(format!("unable to parse value [{}] to float", string_value))
-                    }
-                } else {
-                    match string_value.parse::<i64>() {
-                        Ok(i) => CalcResult::Integer(i),
-                        Err(_) => CalcResult::Error(format!("unable to parse value [{}] to integer", string_value))
-                    }
-                }
-            },
+    fn perform_calc_eval(&self, calc: &str) -> CalcResult {
+        match self.evaluate_calc_value(calc) {
+            Ok(x) => self.finish_result(calc, x),
             Err(error_message) => CalcResult::Error(error_message)
         }
     }
 
-    fn format_prev_calculation(output: &CalcResult) -> String {
-        let mut formatted = String::from("");
-
-        let formatted_output = match &output {
-            CalcResult::Float(value) => value.to_string(),
-            CalcResult::Integer(value) => value.to_string(),
-            CalcResult::Error(string) => String::from(string)
-        };
-        formatted.push_str(&formatted_output);
+    const OUTPUT_BASE_NAMES: [(&'static str, u32); 4] = [
+        ("dec", 10),
+        ("hex", 16),
+        ("oct", 8),
+        ("bin", 2),
+    ];
 
-        return formatted;
+    fn get_base_name(base: u32) -> String {
+        for (name, b) in Calculator::OUTPUT_BASE_NAMES.iter() {
+            if *b == base {
+                return name.to_string();
+            }
+        }
+        format!("base {}", base)
     }
 
-    fn recall_previous_calc(&mut self) {
-        let prev: &(String, CalcResult) =
-            self.prev_calcs.get(
-                self.prev_calcs.len() - usize::from(self.selected_calc))
-                    .unwrap();
-        self.calc.clear();
-        if self.selected_equals {
-            self.calc.push_str(&Calculator::format_prev_calculation(&prev.1));
-        } else {
-            self.calc.push_str(&prev.0);
+    // a calc ending with "in hex", "to bin", "in base 36" (or similar) has its
+    //   result shown in that base -- returns the calc without that ending, and
+    //   the base if there was one
+    fn split_output_base(calc: &str) -> Result<(String, Option<u32>), String> {
+        let (expression, base) = match Calculator::split_last_output_base(calc) {
+            Ok(Some((expression, base))) => (expression, base),
+            Ok(None) => { return Ok((calc.to_string(), None)); },
+            Err(m) => { return Err(m); }
+        };
+        // "1 in hex in bin" can't be shown in both
+        match Calculator::split_last_output_base(&expression) {
+            Ok(None) => Ok((expression, Some(base))),
+            _ => Err("the output base is given twice: only one \"in hex\" (or similar) is allowed".to_string())
         }
-        self.move_cursor_end();
     }
 
-    fn move_cursor_left(&mut self) {
-       if self.selected_calc == 0 {
-            if self.calc_pos > 0 {
-                self.calc_pos -= 1;
+    fn split_last_output_base(calc: &str) -> Result<Option<(String, u32)>, String> {
+        let words: Vec<&str> = calc.split_whitespace().collect();
+        let n = words.len();
+        let is_keyword = |w: &str| w == "in" || w == "to";
+        if n >= 2 && is_keyword(words[n - 2]) {
+            for (name, base) in Calculator::OUTPUT_BASE_NAMES.iter() {
+                if words[n - 1] == *name {
+                    return Ok(Some((words[..n - 2].join(" "), *base)));
+                }
             }
-        } else {
-            self.selected_equals = false;
         }
+        if n >= 3 && is_keyword(words[n - 3]) && words[n - 2] == "base" {
+            return match words[n - 1].parse::<u32>() {
+                Ok(base) if (2..=36).contains(&base) => Ok(Some((words[..n - 3].join(" "), base))),
+                _ => Err(format!("cannot show results in base [{}]: the base must be from 2 to 36", words[n - 1]))
+            };
+        }
+        Ok(None)
     }
 
-    fn move_cursor_right(&mut self) {
-        if self.selected_calc == 0 {
-            if usize::from(self.calc_pos) < self.calc.len() {
-                self.calc_pos += 1;
-            }
-        } else {
-            self.selected_equals = true;
+    fn get_display_base(&self, input: &str) -> u32 {
+        match Calculator::split_output_base(input) {
+            Ok((_, Some(base))) => base,
+            _ => self.output_base
         }
     }
 
-    fn move_cursor_home(&mut self) {
-        self.selected_calc = 0;
-        self.selected_equals = false;
-        self.calc_pos = 0;
+    fn cycle_output_base(&mut self) {
+        let bases: Vec<u32> = Calculator::OUTPUT_BASE_NAMES.iter().map(|b| b.1).collect();
+        self.output_base = match bases.iter().position(|b| *b == self.output_base) {
+            Some(i) => bases[(i + 1) % bases.len()],
+            None => 10
+        };
     }
 
-    fn move_cursor_end(&mut self) {
-        // in case the new calc is shorter than the previous one, start by moving all the way home
-        self.move_cursor_home();
-        while usize::from(self.calc_pos) < self.calc.len() {
-            self.calc_pos += 1;
+    // the integer value of a result, if it has one
+    fn get_integer_result(output: &CalcResult) -> Option<BigInt> {
+        match output {
+            CalcResult::Word(value, _, _) | CalcResult::Factors(value, _) => Some(value.clone()),
+            CalcResult::Decimal(x) if x.is_integer() => Some(x.with_scale(0).as_bigint_and_exponent().0),
+            CalcResult::Fraction(x, _) if x.is_integer() => Some(x.numer().clone()),
+            CalcResult::Boolean(b) => Some(BigInt::from(u8::from(*b))),
+            // f64 can exactly represent integers only up to 2^53
+            CalcResult::Float(value) if value.fract() == 0.0 && value.abs() < 9007199254740992.0 => {
+                Some(BigInt::from(*value as i64))
+            },
+            _ => None
         }
     }
 
-    // get the type of token the cursor is at, then move left
-    //   until the type of token changes
-    // if there are any errors accessing the character at a position
-    //   or matching things we can just return and not move the cursor
-    fn move_cursor_left_token(&mut self) {
-        if self.calc_pos as usize == self.calc.len() {
-            self.move_cursor_left();
+    // the prefixes here match the prefixed literals that can be typed in, so a
+    //   recalled result reads back to the same value
+    fn format_integer_in_base(value: &BigInt, base: u32) -> String {
+        let sign = if value < &BigInt::zero() { "-" } else { "" };
+        let digits = if value < &BigInt::zero() {
+            (-value).to_str_radix(base).to_uppercase()
+        } else {
+            value.to_str_radix(base).to_uppercase()
+        };
+        match base {
+            16 => format!("{}0x{}", sign, digits),
+            8 => format!("{}0o{}", sign, digits),
+            2 => format!("{}0b{}", sign, digits),
+            _ => format!("{}{} (base {})", sign, digits, base)
+        }
+    }
+
+    fn format_prev_calculation_in_base(output: &CalcResult, base: u32) -> String {
+        // programmer mode results are always shown in dec, hex and bin, and
+        //   true or false is the same in any base
+        if base == 10 || matches!(output, CalcResult::Word(..) | CalcResult::Boolean(_)) {
+            return Calculator::format_prev_calculation(output);
+        }
+        if let CalcResult::Error(_) = output {
+            return Calculator::format_prev_calculation(output);
+        }
+        match Calculator::get_integer_result(output) {
+            Some(value) => Calculator::format_integer_in_base(&value, base),
+            None => format!("{} (not an integer, so shown in decimal)", Calculator::format_prev_calculation(output))
+        }
+    }
+
+    // there is no way to type in a literal in bases other than 16, 8, and 2,
+    //   so results in other bases are recalled in decimal
+    fn format_result_for_recall(output: &CalcResult, base: u32) -> String {
+        // "true" and "false" aren't names that can be typed in
+        if let CalcResult::Boolean(b) = output {
+            return Calculator::boolean_to_bigdecimal(*b).to_string();
+        }
+        // a mixed number or a decimal cut short can't be typed back in exactly,
+        //   but "7/3" and "0.(3)" can
+        if let CalcResult::Fraction(x, format) = output {
+            if base == 10 || !x.is_integer() {
+                if *format == CalcFractionFormat::Decimal && x.fraction_digits(Calculator::MAX_REPEATING_DIGITS).is_some() {
+                    return Calculator::format_fraction(x, CalcFractionFormat::Decimal);
+                }
+                return Calculator::format_fraction(x, CalcFractionFormat::Fraction);
+            }
+        }
+        if base == 16 || base == 8 || base == 2 {
+            if let Some(value) = Calculator::get_integer_result(output) {
+                return Calculator::format_integer_in_base(&value, base);
+            }
+        }
+        Calculator::format_prev_calculation(output)
+    }
+
+    const ROUNDING_MODE_NAMES: [(&'static str, RoundingMode); 4] = [
+        ("half-even", RoundingMode::HalfEven),
+        ("half-up", RoundingMode::HalfUp),
+        ("down", RoundingMode::Down),
+        ("up", RoundingMode::Up),
+    ];
+
+    // results can't be rounded to more digits than a division keeps
+    const MAX_PRECISION_DIGITS: u64 = bigmath::DIVISION_DIGITS;
+
+    fn get_rounding_mode_name(mode: RoundingMode) -> String {
+        for (name, m) in Calculator::ROUNDING_MODE_NAMES.iter() {
+            if *m == mode {
+                return name.to_string();
+            }
+        }
+        format!("{:?}", mode)
+    }
+
+    // returns None if the calc is not a rounding setting, or else the words
+    //   of the setting, like ("digits", "6") or ("round", "half-up")
+    fn parse_rounding_setting(calc: &str) -> Option<(String, String)> {
+        let words: Vec<&str> = calc.split_whitespace().collect();
+        if words.len() != 2 {
+            return None;
+        }
+        let is_setting = match words[0] {
+            "digits" | "places" => words[1] == "off" || words[1].chars().all(|c| c.is_ascii_digit()),
+            "round" => Calculator::ROUNDING_MODE_NAMES.iter().any(|(name, _)| *name == words[1]),
+            _ => false
+        };
+        if !is_setting {
+            return None;
+        }
+        Some((words[0].to_string(), words[1].to_string()))
+    }
+
+    fn apply_rounding_setting(&mut self, setting: (String, String)) -> Result<(), String> {
+        let (name, value) = setting;
+        if name == "round" {
+            for (mode_name, mode) in Calculator::ROUNDING_MODE_NAMES.iter() {
+                if *mode_name == value {
+                    self.rounding_mode = *mode;
+                }
+            }
+            return Ok(());
+        }
+        if value == "off" {
+            self.precision = None;
+            return Ok(());
+        }
+        let digits = match value.parse::<u64>() {
+            Ok(d) if d <= Calculator::MAX_PRECISION_DIGITS => d,
+            _ => { return Err(format!("cannot round to [{}] {}: the most is {}", value, name, Calculator::MAX_PRECISION_DIGITS)); }
+        };
+        if name == "digits" {
+            if digits == 0 {
+                return Err("cannot round to [0] significant digits: the fewest is 1".to_string());
+            }
+            self.precision = Some(CalcPrecision::SignificantDigits(digits));
+        } else {
+            self.precision = Some(CalcPrecision::DecimalPlaces(digits));
+        }
+        Ok(())
+    }
+
+    fn cycle_rounding_mode(&mut self) {
+        let modes: Vec<RoundingMode> = Calculator::ROUNDING_MODE_NAMES.iter().map(|m| m.1).collect();
+        self.rounding_mode = match modes.iter().position(|m| *m == self.rounding_mode) {
+            Some(i) => modes[(i + 1) % modes.len()],
+            None => RoundingMode::HalfEven
+        };
+    }
+
+    fn format_rounding(&self) -> String {
+        let mode = Calculator::get_rounding_mode_name(self.rounding_mode);
+        match self.precision {
+            Some(CalcPrecision::SignificantDigits(d)) => format!("rounding: {} significant digits, {}", d, mode),
+            Some(CalcPrecision::DecimalPlaces(d)) => format!("rounding: {} decimal places, {}", d, mode),
+            None => format!("rounding: {} (for divisions that don't terminate)", mode)
+        }
+    }
+
+    fn round_to_precision(&self, x: &BigDecimal) -> BigDecimal {
+        match self.precision {
+            Some(CalcPrecision::SignificantDigits(d)) => bigmath::round_to_significant_digits(x, d, self.rounding_mode),
+            Some(CalcPrecision::DecimalPlaces(d)) => bigmath::round_to_places(x, d as i64, self.rounding_mode),
+            None => x.clone()
+        }
+    }
+
+    // with a precision set the result is rounded, and otherwise a result
+    //   that was kept exact is shown exactly: a repeating decimal, like 1/7,
+    //   is kept as a fraction so its repeating digits can be shown, and
+    //   "0.(3)*3" is exactly 1 rather than 0.999...
+    fn finish_result(&self, expression: &str, value: CalcValue) -> CalcResult {
+        if Calculator::is_boolean_expression(expression) {
+            return CalcResult::Boolean(!value.is_zero());
+        }
+        if Calculator::is_whole_call(expression, "factor") && value.is_integer() {
+            let n = value.to_bigdecimal(self.rounding_mode).with_scale(0).as_bigint_and_exponent().0;
+            return match bigmath::prime_factors(&n) {
+                Ok(factors) => CalcResult::Factors(n, factors),
+                Err(m) => CalcResult::Error(m)
+            };
+        }
+        if Calculator::is_whole_call(expression, "approx") {
+            let fraction = match value {
+                CalcValue::Exact(f) => f,
+                // with a constant or function in its arguments, the
+                //   fraction is found again from its decimal
+                CalcValue::Inexact(x, _) => match Rational::from_rounded_bigdecimal(&x, bigmath::DIVISION_DIGITS) {
+                    Ok(f) => f,
+                    Err(m) => { return CalcResult::Error(m); }
+                }
+            };
+            return CalcResult::Fraction(fraction, CalcFractionFormat::Fraction);
+        }
+        if self.precision.is_some() {
+            return self.round_result(CalcResult::Decimal(value.to_known_digits(self.rounding_mode)));
+        }
+        match value {
+            CalcValue::Exact(x) if !x.has_terminating_decimal() => CalcResult::Fraction(x, CalcFractionFormat::Decimal),
+            _ => CalcResult::Decimal(value.to_known_digits(self.rounding_mode))
+        }
+    }
+
+    // an approximation is shown as a fraction, and a factorization as its
+    //   factors, when it's the whole calculation, like "approx(pi, 1e-6)"
+    //   but not "approx(pi, 1e-6) * 2"
+    fn is_whole_call(expression: &str, name: &str) -> bool {
+        let tokens = match Calculator::parse_calc_to_tokens(expression) {
+            Ok(t) => t,
+            Err(_) => { return false; }
+        };
+        if tokens.len() < 3 || tokens[0] != name || tokens[1] != "(" {
+            return false;
+        }
+        let mut depth = 0;
+        for (i, token) in tokens.iter().enumerate().skip(1) {
+            if token == "(" {
+                depth += 1;
+            } else if token == ")" {
+                depth -= 1;
+                if depth == 0 {
+                    return i == tokens.len() - 1;
+                }
+            }
+        }
+        false
+    }
+
+    // a calc is shown as true or false when the last thing it does is compare,
+    //   like "x + 1 > 2", or combine comparisons, like "not (x > 2 and x < 5)"
+    fn is_boolean_expression(expression: &str) -> bool {
+        let tree = match Calculator::build_calc_eval_tree(expression) {
+            Ok(t) => t,
+            Err(_) => { return false; }
+        };
+        let mut node = tree.get_root().and_then(|r| tree.node_at(r));
+        // parens around the whole calc don't change what it does last
+        while let Some(n) = node {
+            if n.value.token_type != CalcParseToken::CloseParen {
+                break;
+            }
+            node = n.get_left().and_then(|l| tree.node_at(l));
+        }
+        match node {
+            Some(n) => match n.value.token_type {
+                CalcParseToken::Operator => CalcParseToken::BOOLEAN_OPERATORS.contains(&n.value.string_value.as_str()),
+                CalcParseToken::UnaryOperator => n.value.string_value == "not",
+                CalcParseToken::Identifier => n.value.string_value == "near" && n.has_left(),
+                _ => false
+            },
+            None => false
+        }
+    }
+
+    // a selected result that isn't an integer is shown with the best
+    //   fractions that approximate it
+    const MAX_CONVERGENTS: usize = 8;
+
+    fn get_convergents_result(&self) -> Option<Rational> {
+        if self.selected_calc == 0 {
+            return None;
+        }
+        let x = match self.prev_calcs.get(self.prev_calcs.len() - usize::from(self.selected_calc)) {
+            Some((_, CalcResult::Decimal(x))) => Rational::from_bigdecimal(x),
+            Some((_, CalcResult::Fraction(x, _))) => x.clone(),
+            _ => { return None; }
+        };
+        if x.is_integer() {
+            return None;
+        }
+        Some(x)
+    }
+
+    fn format_convergents(x: &Rational) -> String {
+        let convergents = x.convergents();
+        let mut formatted: Vec<String> = convergents.iter().take(Calculator::MAX_CONVERGENTS)
+            .map(|c| Calculator::format_fraction(c, CalcFractionFormat::Fraction))
+            .collect();
+        if convergents.len() > Calculator::MAX_CONVERGENTS {
+            formatted.push("...".to_string());
+        }
+        format!("convergents: {}", formatted.join(", "))
+    }
+
+    fn round_result(&self, result: CalcResult) -> CalcResult {
+        match result {
+            CalcResult::Decimal(x) => CalcResult::Decimal(self.round_to_precision(&x)),
+            _ => result
+        }
+    }
+
+    // these are also the names of the units, so "30deg" is 30 degrees in
+    //   whatever the angle mode is, like "30 * deg"
+    const ANGLE_UNIT_NAMES: [(&'static str, AngleUnit); 3] = [
+        ("rad", AngleUnit::Radians),
+        ("deg", AngleUnit::Degrees),
+        ("grad", AngleUnit::Gradians),
+    ];
+
+    fn get_angle_unit(name: &str) -> Option<AngleUnit> {
+        for (n, unit) in Calculator::ANGLE_UNIT_NAMES.iter() {
+            if *n == name {
+                return Some(*unit);
+            }
+        }
+        None
+    }
+
+    fn get_angle_unit_name(unit: AngleUnit) -> String {
+        for (name, u) in Calculator::ANGLE_UNIT_NAMES.iter() {
+            if *u == unit {
+                return name.to_string();
+            }
+        }
+        format!("{:?}", unit)
+    }
+
+    fn cycle_angle_mode(&mut self) {
+        let units: Vec<AngleUnit> = Calculator::ANGLE_UNIT_NAMES.iter().map(|u| u.1).collect();
+        self.angle_mode = match units.iter().position(|u| *u == self.angle_mode) {
+            Some(i) => units[(i + 1) % units.len()],
+            None => AngleUnit::Radians
+        };
+    }
+
+    // the size of one of the unit in the angle mode, like pi/180 for "deg"
+    //   when the angle mode is radians
+    fn get_angle_unit_size(&self, unit: AngleUnit) -> BigDecimal {
+        bigmath::angle_unit_size(unit, self.angle_mode)
+    }
+
+    // only an angle already in the angle mode's unit is kept exact
+    fn get_angle_unit_size_exact(&self, unit: AngleUnit) -> Result<Rational, String> {
+        if unit == self.angle_mode {
+            return Ok(Rational::from_integer(BigInt::from(1)));
+        }
+        Err(format!("an angle in [{}] cannot be calculated exactly in [{}] mode",
+            Calculator::get_angle_unit_name(unit), Calculator::get_angle_unit_name(self.angle_mode)))
+    }
+
+    const FRACTION_FORMAT_NAMES: [(&'static str, CalcFractionFormat); 3] = [
+        ("fraction", CalcFractionFormat::Fraction),
+        ("mixed", CalcFractionFormat::Mixed),
+        ("decimal", CalcFractionFormat::Decimal),
+    ];
+
+    fn get_fraction_format_name(format: CalcFractionFormat) -> String {
+        match format {
+            CalcFractionFormat::Fraction => "fractions",
+            CalcFractionFormat::Mixed => "mixed numbers",
+            CalcFractionFormat::Decimal => "decimals"
+        }.to_string()
+    }
+
+    // a calc ending with "as fraction", "as mixed" or "as decimal" is
+    //   calculated exactly -- returns the calc without that ending, and the
+    //   format if there was one
+    fn split_fraction_format(calc: &str) -> (String, Option<CalcFractionFormat>) {
+        let words: Vec<&str> = calc.split_whitespace().collect();
+        let n = words.len();
+        if n >= 2 && words[n - 2] == "as" {
+            for (name, format) in Calculator::FRACTION_FORMAT_NAMES.iter() {
+                if words[n - 1] == *name {
+                    return (words[..n - 2].join(" "), Some(*format));
+                }
+            }
+        }
+        (calc.to_string(), None)
+    }
+
+    // None if the calc is done with the usual decimals
+    fn get_fraction_format(&self, input: &str) -> Option<CalcFractionFormat> {
+        Calculator::get_requested_fraction_format(input).or(self.rational_mode)
+    }
+
+    // the format the calc itself ends with, rather than the rational mode
+    fn get_requested_fraction_format(input: &str) -> Option<CalcFractionFormat> {
+        let calc_no_base = match Calculator::split_output_base(input) {
+            Ok((calc, _)) => calc,
+            Err(_) => input.to_string()
+        };
+        Calculator::split_fraction_format(&calc_no_base).1
+    }
+
+    fn cycle_rational_mode(&mut self) {
+        self.rational_mode = match self.rational_mode {
+            None => Some(CalcFractionFormat::Fraction),
+            Some(CalcFractionFormat::Fraction) => Some(CalcFractionFormat::Mixed),
+            Some(CalcFractionFormat::Mixed) => Some(CalcFractionFormat::Decimal),
+            Some(CalcFractionFormat::Decimal) => None
+        };
+    }
+
+    // a repeating decimal is only shown with its repeating digits in parens
+    //   when they fit in as many digits as a division keeps
+    const MAX_REPEATING_DIGITS: usize = bigmath::DIVISION_DIGITS as usize;
+
+    fn format_fraction(x: &Rational, format: CalcFractionFormat) -> String {
+        if x.is_integer() {
+            return x.numer().to_string();
+        }
+        match format {
+            CalcFractionFormat::Mixed => {
+                // the whole part is truncated toward zero, so "-7/3" is "-2 1/3"
+                let whole = x.numer() / x.denom();
+                if !whole.is_zero() {
+                    let remainder = x.numer() % x.denom();
+                    let remainder = if remainder < BigInt::zero() { -remainder } else { remainder };
+                    return format!("{} {}/{}", whole, remainder, x.denom());
+                }
+            },
+            CalcFractionFormat::Decimal => {
+                if let Some((non_repeating, repetend)) = x.fraction_digits(Calculator::MAX_REPEATING_DIGITS) {
+                    if !repetend.is_empty() {
+                        let sign = if x.numer() < &BigInt::zero() { "-" } else { "" };
+                        let whole = x.numer().abs() / x.denom();
+                        return format!("{}{}.{}({})", sign, whole, non_repeating, repetend);
+                    }
+                }
+                return Calculator::format_decimal(&x.to_bigdecimal(RoundingMode::HalfEven));
+            },
+            CalcFractionFormat::Fraction => ()
+        }
+        format!("{}/{}", x.numer(), x.denom())
+    }
+
+    const WORD_BITS: [u32; 4] = [8, 16, 32, 64];
+
+    // like "u8" or "i64"
+    fn get_word_size_name(word_size: CalcWordSize) -> String {
+        format!("{}{}", if word_size.signed { "i" } else { "u" }, word_size.bits)
+    }
+
+    // programmer mode starts out with a signed 64-bit word
+    fn toggle_programmer_mode(&mut self) {
+        self.word_size = match self.word_size {
+            Some(_) => None,
+            None => Some(CalcWordSize { bits: 64, signed: true })
+        };
+    }
+
+    fn cycle_word_bits(&mut self) {
+        if let Some(word_size) = self.word_size.as_mut() {
+            let next = match Calculator::WORD_BITS.iter().position(|b| *b == word_size.bits) {
+                Some(i) => i + 1,
+                None => 0
+            };
+            word_size.bits = Calculator::WORD_BITS[next % Calculator::WORD_BITS.len()];
+        }
+    }
+
+    fn toggle_word_signed(&mut self) {
+        if let Some(word_size) = self.word_size.as_mut() {
+            word_size.signed = !word_size.signed;
+        }
+    }
+
+    // each value is wrapped around to fit the word as it's calculated, so an
+    //   overflow partway through is flagged even when the result fits
+    fn perform_calc_eval_in_word(&self, calc: &str, word_size: CalcWordSize) -> CalcResult {
+        let tree = match Calculator::build_calc_eval_tree(calc) {
+            Ok(t) => t,
+            Err(m) => { return CalcResult::Error(m); }
+        };
+        let mut state = CalcEvalState::new(Some(word_size));
+        let value = match self.evaluate_tree(&tree, &BTreeMap::new(), 0, &mut state) {
+            Ok(x) => x.to_known_digits(self.rounding_mode),
+            Err(m) => { return CalcResult::Error(m); }
+        };
+        match bigmath::wrap_to_word(&value, word_size.bits, word_size.signed) {
+            Ok((wrapped, overflowed)) => CalcResult::Word(wrapped, word_size, overflowed || state.overflowed),
+            Err(m) => CalcResult::Error(m)
+        }
+    }
+
+    // the bits of a word as all digits of the given base (16 or 2), where
+    //   negative values are shown in two's complement
+    fn get_word_digits(value: &BigInt, word_size: CalcWordSize, base: u32) -> String {
+        let unsigned = if value < &BigInt::zero() {
+            value + (BigInt::from(1) << (word_size.bits as usize))
+        } else {
+            value.clone()
+        };
+        let width = (word_size.bits / base.trailing_zeros()) as usize;
+        format!("{:0>width$}", unsigned.to_str_radix(base).to_uppercase(), width = width)
+    }
+
+    // digits are grouped by 4 with "_", so the result can be typed back in
+    fn format_word_bits(value: &BigInt, word_size: CalcWordSize, base: u32) -> String {
+        let digits: Vec<char> = Calculator::get_word_digits(value, word_size, base).chars().collect();
+        let groups: Vec<String> = digits.chunks(4).map(|g| g.iter().collect()).collect();
+        let prefix = if base == 16 { "0x" } else { "0b" };
+        format!("{}{}", prefix, groups.join("_"))
+    }
+
+    // the selected previous calc, or else the most recent one, that has a
+    //   programmer mode result
+    fn get_bit_grid_result(&self) -> Option<(&BigInt, CalcWordSize)> {
+        if self.selected_calc > 0 {
+            return match self.prev_calcs.get(self.prev_calcs.len() - usize::from(self.selected_calc)) {
+                Some((_, CalcResult::Word(value, word_size, _))) => Some((value, *word_size)),
+                _ => None
+            };
+        }
+        for (_, result) in self.prev_calcs.iter().rev() {
+            if let CalcResult::Word(value, word_size, _) = result {
+                return Some((value, *word_size));
+            }
+        }
+        None
+    }
+
+    // 16 bits to a line, highest bits first, with the positions of the
+    //   highest and lowest bit of each line at either end
+    fn format_bit_grid(value: &BigInt, word_size: CalcWordSize) -> Vec<String> {
+        let digits: Vec<char> = Calculator::get_word_digits(value, word_size, 2).chars().collect();
+        let mut lines = Vec::<String>::new();
+        for (i, line_digits) in digits.chunks(16).enumerate() {
+            let highest = word_size.bits as usize - 1 - i * 16;
+            let lowest = highest + 1 - line_digits.len();
+            let groups: Vec<String> = line_digits.chunks(4).map(|g| g.iter().collect()).collect();
+            lines.push(format!("{:>2}  {}  {}", highest, groups.join(" "), lowest));
+        }
+        lines
+    }
+
+    // trailing zeros after the decimal point only reflect how the result was
+    //   calculated, like the "3.0" from "1.5 * 2", so they are dropped
+    fn format_decimal(x: &BigDecimal) -> String {
+        let formatted = x.to_string();
+        if !formatted.contains('.') {
+            return formatted;
+        }
+        formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+
+    // like "2^3 * 3 * 5", which can be typed back in -- a negative value
+    //   is shown like "-2^2 * 3", which is "-(2^2) * 3"
+    fn format_factors(value: &BigInt, factors: &[(BigInt, u32)]) -> String {
+        let mut formatted: Vec<String> = factors.iter().map(|(p, power)| {
+            if *power == 1 {
+                p.to_string()
+            } else {
+                format!("{}^{}", p, power)
+            }
+        }).collect();
+        if formatted.is_empty() {
+            formatted.push("1".to_string());
+        }
+        let sign = if value < &BigInt::zero() { "-" } else { "" };
+        format!("{}{}", sign, formatted.join(" * "))
+    }
+
+    fn format_prev_calculation(output: &CalcResult) -> String {
+        let mut formatted = String::from("");
+
+        let formatted_output = match &output {
+            CalcResult::Float(value) => value.to_string(),
+            CalcResult::Decimal(x) => Calculator::format_decimal(x),
+            CalcResult::Fraction(x, format) => Calculator::format_fraction(x, *format),
+            CalcResult::Word(value, word_size, overflowed) => {
+                let mut columns = format!("{} | {} | {}",
+                    value,
+                    Calculator::format_word_bits(value, *word_size, 16),
+                    Calculator::format_word_bits(value, *word_size, 2));
+                if *overflowed {
+                    columns.push_str(&format!(" (overflow: wrapped around to fit {})", Calculator::get_word_size_name(*word_size)));
+                }
+                columns
+            },
+            CalcResult::Factors(value, factors) => Calculator::format_factors(value, factors),
+            CalcResult::Boolean(b) => b.to_string(),
+            CalcResult::Error(string) => String::from(string)
+        };
+        formatted.push_str(&formatted_output);
+
+        formatted
+    }
+
+    fn recall_previous_calc(&mut self) {
+        let prev: &(String, CalcResult) =
+            self.prev_calcs.get(
+                self.prev_calcs.len() - usize::from(self.selected_calc))
+                    .unwrap();
+        self.calc.clear();
+        if self.selected_equals {
+            self.calc.push_str(&Calculator::format_result_for_recall(&prev.1, self.get_display_base(&prev.0)));
+        } else {
+            self.calc.push_str(&prev.0);
+        }
+        self.move_cursor_end();
+    }
+
+    fn move_cursor_left(&mut self) {
+       if self.selected_calc == 0 {
+            if self.calc_pos > 0 {
+                self.calc_pos -= 1;
+            }
+        } else {
+            self.selected_equals = false;
+        }
+    }
+
+    fn move_cursor_right(&mut self) {
+        if self.selected_calc == 0 {
+            if usize::from(self.calc_pos) < self.get_calc_char_count() {
+                self.calc_pos += 1;
+            }
+        } else {
+            self.selected_equals = true;
+        }
+    }
+
+    fn move_cursor_home(&mut self) {
+        self.selected_calc = 0;
+        self.selected_equals = false;
+        self.calc_pos = 0;
+    }
+
+    fn move_cursor_end(&mut self) {
+        // in case the new calc is shorter than the previous one, start by moving all the way home
+        self.move_cursor_home();
+        while usize::from(self.calc_pos) < self.get_calc_char_count() {
+            self.calc_pos += 1;
+        }
+    }
+
+    // get the type of token the cursor is at, then move left
+    //   until the type of token changes
+    // if there are any errors accessing the character at a position
+    //   or matching things we can just return and not move the cursor
+    fn move_cursor_left_token(&mut self) {
+        if self.calc_pos as usize == self.get_calc_char_count() {
+            self.move_cursor_left();
         }
         let start_token = match self.get_token_type_at_pos(self.calc_pos) {
             Some(t) => t,
@@ -593,7 +2594,7 @@ impl Calculator {
             None => { return; }
         };
         let mut have_moved = false;
-        while (self.calc_pos as usize) < self.calc.len() {
+        while (self.calc_pos as usize) < self.get_calc_char_count() {
             let pos_token = match self.get_token_type_at_pos(self.calc_pos + 1) {
                 Some(t) => t,
                 None => { return; }
@@ -610,54 +2611,139 @@ impl Calculator {
     }
 
     fn get_token_type_at_pos(&mut self, pos: u16) -> Option<CalcJumpToken> {
+        for (start, end) in CalcJumpToken::find_base_literals(&self.calc).iter() {
+            if (*start..*end).contains(&(pos as usize)) {
+                return Some(CalcJumpToken::BaseLiteral);
+            }
+        }
         // to get Nth char, first skip N chars
-        let pos_char = match self.calc.chars().skip(pos as usize).next() {
+        let pos_char = match self.calc.chars().nth(pos as usize) {
             Some(c) => c,
             None => { return None; }
         };
-        return CalcJumpToken::get_token_matching_char(pos_char);
+        CalcJumpToken::get_token_matching_char(pos_char)
     }
 
-    fn parse_calc_to_tokens(calc: &str) -> Vec<String> {
-        let calc_no_space = calc.replace(" ", "").replace("$", "").replace(",", "");
-        if calc_no_space.len() == 0 {
-            return vec![];
+    // commas directly inside a function call's parens, like "max(1, 2)",
+    //   separate the function's arguments -- anywhere else, like "$1,000"
+    //   or "max((1,000), 2)", they are thousands separators and are dropped
+    fn parse_calc_to_tokens(calc: &str) -> Result<Vec<String>, String> {
+        let calc_no_dollar = calc.replace("$", "");
+        if calc_no_dollar.trim().is_empty() {
+            return Ok(vec![]);
         }
+        let chars: Vec<char> = calc_no_dollar.chars().collect();
         let mut first_char = true;
         let mut tokens: Vec<String> = vec![];
         // initial token type is Operator to allow the calculation to start
         //   with a negative number
         let mut last_token_type = CalcParseToken::Operator;
         let mut token = String::from("");
-        for c in calc_no_space.chars() {
-            // TODO: return Err somehow, like any other parsing error?
-            // perhaps the grammar, in terms of which tokens are allowed where,
-            //   should just be handled elsewhere, and since we don't allow
-            //   invalid chars to be typed in the first place, we can just
-            //   panic here if we see an invalid char
-            let token_type =
-               CalcParseToken::get_token_matching_char(c).expect("Unparseable calculation");
-            if first_char {
-                first_char = false;
-                if c == '-' {
-                    last_token_type = CalcParseToken::Value;
-                } else {
-                    last_token_type = token_type;
-                }
-            } else if c == '-' &&
-                    (last_token_type == CalcParseToken::Operator || last_token_type == CalcParseToken::OpenParen) {
+        // for each currently open paren, whether it belongs to a function call
+        let mut function_parens: Vec<bool> = vec![];
+        // spaces are dropped, but they do end a name, so that word operators
+        //   like "of" and "mod" are not joined with the names around them
+        let mut after_space = false;
+        for (i, c) in chars.iter().copied().enumerate() {
+            if c == ' ' {
+                after_space = true;
+                continue;
+            }
+            let separated_by_space = after_space;
+            after_space = false;
+            if c == '%' {
                 if !token.is_empty() {
                     tokens.push(token.clone());
                 }
                 token.clear();
-                last_token_type = CalcParseToken::Value;
-            // start a new token if token types are different, or if it's any
-            //   type aside from value (digits can repeat, but parens and operators cannot)
-            } else if token_type == CalcParseToken::Value && token == "-" &&
+                if Calculator::is_remainder_sign(&chars[i + 1..]) {
+                    token.push_str("mod");
+                    last_token_type = CalcParseToken::Operator;
+                } else {
+                    token.push(c);
+                    last_token_type = CalcParseToken::Postfix;
+                }
+                first_char = false;
+                continue;
+            } else if c == ',' {
+                if function_parens.last() == Some(&true) {
+                    if !token.is_empty() {
+                        tokens.push(token.clone());
+                    }
+                    token.clear();
+                    token.push(c);
+                    last_token_type = CalcParseToken::Operator;
+                }
+                continue;
+            } else if c == '(' {
+                function_parens.push(last_token_type == CalcParseToken::Identifier);
+            } else if c == ')' {
+                function_parens.pop();
+            }
+            // invalid chars can't be typed in, but a calc can still get here
+            //   with one, like a pasted "1 ? 2"
+            let token_type = match CalcParseToken::get_token_matching_char(c) {
+                Some(t) => t,
+                None => { return Err(format!("unable to read [{}] in the calculation", c)); }
+            };
+            if first_char {
+                first_char = false;
+                if c == '-' {
+                    last_token_type = CalcParseToken::Value;
+                } else {
+                    last_token_type = token_type;
+                }
+            } else if c == '-' &&
+                    (last_token_type == CalcParseToken::Operator || last_token_type == CalcParseToken::OpenParen ||
+                        last_token_type == CalcParseToken::UnaryOperator) {
+                if !token.is_empty() {
+                    tokens.push(token.clone());
+                }
+                token.clear();
+                last_token_type = CalcParseToken::Value;
+            } else if c == '/' && token == "/" {
+                // do nothing: append second "/" (done below) to make the "//" operator
+            } else if (c == '<' && token == "<") || (c == '>' && token == ">") {
+                // do nothing: append second "<" or ">" (done below) to make a shift operator
+            } else if c == '=' && !separated_by_space && (token == "<" || token == ">" || token == "=" || token == "!") {
+                // append "=" (done below) to make a comparison, like "<=" or "==" -- a
+                //   "!" is a factorial otherwise, so "!=" is an operator from here on
+                last_token_type = CalcParseToken::Operator;
+            } else if last_token_type == CalcParseToken::Value && !separated_by_space &&
+                    (token == "0" || token == "-0") &&
+                    CalcParseToken::get_base_prefix_radix(c).is_some() &&
+                    chars.get(i + 1).is_some_and(|d| d.is_digit(CalcParseToken::get_base_prefix_radix(c).unwrap())) {
+                // do nothing: append the base prefix (done below), like the "x" in "0xFF"
+            } else if last_token_type == CalcParseToken::Value && !separated_by_space &&
+                    Calculator::get_base_literal_radix(&token).is_some_and(|r| c.is_digit(r) || c == '_') {
+                // do nothing: append a digit (done below) to a prefixed literal, like "0xFF"
+            } else if last_token_type == CalcParseToken::Value && !separated_by_space &&
+                    (c == 'e' || c == 'E') && !token.contains(['e', 'E']) &&
+                    Calculator::is_exponent_start(&chars[i + 1..]) {
+                // do nothing: append "e" (done below) to make a value in scientific
+                //   notation, like "6.022e23"
+            } else if (c == '-' || c == '+') && last_token_type == CalcParseToken::Value &&
+                    (token.ends_with('e') || token.ends_with('E')) {
+                // do nothing: append the exponent's sign (done below), like "1.5e-9"
+            } else if c == '(' && last_token_type == CalcParseToken::Value && !separated_by_space &&
+                    token.contains('.') && !token.contains(['e', 'E', '(']) &&
+                    Calculator::is_repetend_start(&chars[i + 1..]) {
+                // do nothing: append "(" (done below) to start the repeating
+                //   digits of a decimal, like "0.(3)"
+            } else if c == ')' && last_token_type == CalcParseToken::Value && token.contains('(') && !token.ends_with(')') {
+                // do nothing: append ")" (done below) to end the repeating digits
+            } else if last_token_type == CalcParseToken::Identifier && c.is_ascii_digit() && !separated_by_space {
+                // do nothing: append digit (done below) to the identifier, like "log10"
+            // start a new token if token types are different, or if it's any
+            //   type aside from value or identifier (digits and letters can repeat,
+            //   but parens and operators cannot)
+            } else if token_type == CalcParseToken::Value && token == "-" &&
                     !tokens.is_empty() &&
                     CalcParseToken::get_token_matching_str(tokens.as_slice().last().unwrap()).unwrap() == CalcParseToken::Operator {
                 // do nothing: append value (done below) to previous "-" to make it a negative value
-            } else if last_token_type != token_type || token_type != CalcParseToken::Value {
+            } else if last_token_type != token_type ||
+                    (token_type != CalcParseToken::Value && token_type != CalcParseToken::Identifier) ||
+                    (token_type == CalcParseToken::Identifier && separated_by_space) {
                 if !token.is_empty() {
                     tokens.push(token.clone());
                 }
@@ -666,14 +2752,129 @@ impl Calculator {
             }
             token.push(c);
         }
-        tokens.push(token.clone());
-        return tokens;
+        if !token.is_empty() {
+            tokens.push(token.clone());
+        }
+        let tokens = Calculator::split_negative_power_bases(tokens);
+        Ok(Calculator::insert_implicit_multiplication(tokens))
+    }
+
+    // a negative number is negated after it's raised to a power or has its
+    //   factorial taken, so "-2^2" is "-(2^2)" and "-3!" is "-(3!)" like
+    //   they're written on paper -- its "-" becomes a prefix operator
+    //   instead (see build_calc_eval_tree())
+    fn split_negative_power_bases(tokens: Vec<String>) -> Vec<String> {
+        let mut result: Vec<String> = vec![];
+        for (i, token) in tokens.iter().enumerate() {
+            let is_power_base = tokens.get(i + 1).is_some_and(|t| t == "^" || t == "!");
+            if is_power_base && token.len() > 1 && token.starts_with('-') &&
+                    CalcParseToken::get_token_matching_str(token) == Some(CalcParseToken::Value) {
+                result.push("-".to_string());
+                result.push(token[1..].to_string());
+            } else {
+                result.push(token.clone());
+            }
+        }
+        result
+    }
+
+    // the base of a partially tokenized prefixed literal, like "0x" or "-0xF"
+    fn get_base_literal_radix(token: &str) -> Option<u32> {
+        let unsigned = token.strip_prefix('-').unwrap_or(token);
+        let mut chars = unsigned.chars();
+        if chars.next() != Some('0') {
+            return None;
+        }
+        chars.next().and_then(CalcParseToken::get_base_prefix_radix)
+    }
+
+    // an open paren directly after a number with a decimal point starts its
+    //   repeating digits if it holds only digits, so "0.(3)" is 1/3 but
+    //   "1.5(2)" is also a repeating decimal -- use "1.5 (2)" or "1.5*2" to
+    //   multiply
+    fn is_repetend_start(rest: &[char]) -> bool {
+        let digits = rest.iter().take_while(|c| c.is_ascii_digit()).count();
+        digits > 0 && rest.get(digits) == Some(&')')
+    }
+
+    // an "e" directly after a number starts its exponent only if it's directly
+    //   followed by digits, optionally signed, like "1e-3" -- otherwise it's
+    //   the constant "e", so "2e" is "2*e" and "1e - 3" is "1*e - 3"
+    fn is_exponent_start(rest: &[char]) -> bool {
+        let digits = match rest.first() {
+            Some('-') | Some('+') => &rest[1..],
+            _ => rest
+        };
+        match digits.first() {
+            Some(c) => c.is_ascii_digit(),
+            None => false
+        }
+    }
+
+    // "%" is the remainder operator only when it's followed by something
+    //   that starts a value, like "7 % 3" or "7 % (1+2)" -- otherwise it's a
+    //   percent sign, like "200 + 15%", "200 - 15% - 5" or "15% of 80"
+    // a sign written right before a value makes a signed divisor, so
+    //   "7%-3" and "7 % -3" are remainders while "15% - 5" is not
+    fn is_remainder_sign(rest: &[char]) -> bool {
+        let rest: String = rest.iter().collect();
+        let rest = rest.trim_start();
+        let rest = match rest.strip_prefix(['-', '+']) {
+            Some(unsigned) if unsigned.starts_with(|c: char| !c.is_whitespace() && c != '-' && c != '+') => unsigned,
+            _ => rest
+        };
+        let next = match rest.chars().next() {
+            Some(c) => c,
+            None => { return false; }
+        };
+        if next.is_ascii_digit() || next == '.' || next == '(' || next == '~' || next == CalcParseToken::HISTORY_REFERENCE_CHAR {
+            return true;
+        }
+        if CalcParseToken::IDENTIFIER_CHARS.contains(next) {
+            let word: String = rest.chars()
+                .take_while(|c| c.is_ascii_digit() || CalcParseToken::IDENTIFIER_CHARS.contains(*c))
+                .collect();
+            // like the "of" in "15% of 80"
+            return CalcParseToken::get_operator_precedence(&word).is_none();
+        }
+        false
+    }
+
+    // a value or close paren directly followed by a value, name, or open paren
+    //   is multiplied, like "2(3+4)", "(1+2)(3+4)", or "3pi" -- an explicit "*"
+    //   is inserted, so implicit multiplication has exactly the same precedence
+    //   as "*" and is evaluated left to right along with "*" and "/", meaning
+    //   "1/2(3)" is "(1/2)*3" not "1/(2*3)"
+    // a name directly followed by an open paren is always a function call, so
+    //   "x(2)" is not a multiplication even when "x" is a variable
+    fn insert_implicit_multiplication(tokens: Vec<String>) -> Vec<String> {
+        let mut result: Vec<String> = vec![];
+        let mut last_token_type: Option<CalcParseToken> = None;
+        for token in tokens.into_iter() {
+            let token_type = CalcParseToken::get_token_matching_str(&token);
+            match (last_token_type, token_type) {
+                (Some(CalcParseToken::Value), Some(CalcParseToken::OpenParen)) |
+                (Some(CalcParseToken::Value), Some(CalcParseToken::Identifier)) |
+                (Some(CalcParseToken::CloseParen), Some(CalcParseToken::OpenParen)) |
+                (Some(CalcParseToken::CloseParen), Some(CalcParseToken::Identifier)) |
+                (Some(CalcParseToken::CloseParen), Some(CalcParseToken::Value)) => {
+                    result.push("*".to_string());
+                },
+                _ => ()
+            }
+            last_token_type = token_type;
+            result.push(token);
+        }
+        result
     }
 
     fn build_calc_eval_tree(calc: &str) -> Result<Tree<CalcEvalItem>, String> {
         let mut tree = Tree::<CalcEvalItem>::new();
 
-        let tokens = Calculator::parse_calc_to_tokens(calc);
+        let tokens = match Calculator::parse_calc_to_tokens(calc) {
+            Ok(t) => t,
+            Err(m) => { return Err(m); }
+        };
 
         let mut cursor: Option<NodeIndex> = None;
 
@@ -682,9 +2883,16 @@ impl Calculator {
             if token_type.is_none() {
                 return Err(format!("unknown token: [{}]", token));
             }
-            let token_type = token_type.unwrap();
+            let mut token_type = token_type.unwrap();
+            // a "-" or "+" where an operand is expected is a prefix operator,
+            //   like in "-(3+4)", "2*-x" or "--5"
+            if CalcParseToken::is_sign(token) && Calculator::is_expecting_operand(&tree, cursor) {
+                token_type = CalcParseToken::UnaryOperator;
+            }
             match token_type {
-                CalcParseToken::Value => {
+                // an identifier is placed in the tree like a value, and becomes a
+                //   function call if an open paren follows it
+                CalcParseToken::Value | CalcParseToken::Identifier => {
                     let val_node_idx = Some(tree.add_node(TreeNode::new(
                         CalcEvalItem::new(token_type, token))));
                     match cursor {
@@ -694,7 +2902,7 @@ impl Calculator {
                                 None => { return Err("no node exists at cursor index location".to_string()); }
                             };
                             match cursor_node.value.token_type {
-                                CalcParseToken::Value => { return Err("cannot have two consecutive values: expected an operation or open paren".to_string()); },
+                                CalcParseToken::Value | CalcParseToken::Identifier => { return Err("cannot have two consecutive values: expected an operation or open paren".to_string()); },
                                 CalcParseToken::Operator => {
                                     if !cursor_node.has_left() {
                                         return Err("cursor is an operator without a left hand side value".to_string());
@@ -721,6 +2929,18 @@ impl Calculator {
                                 },
                                 CalcParseToken::CloseParen => {
                                     return Err("expected an operator, not a value, since the cursor was at a close paren".to_string());
+                                },
+                                CalcParseToken::Postfix => {
+                                    return Err("expected an operator, not a value, after a percent sign".to_string());
+                                },
+                                CalcParseToken::UnaryOperator => {
+                                    if cursor_node.has_left() {
+                                        return Err("cursor is a unary operator that already has an operand".to_string());
+                                    }
+                                    if tree.set_node_child(c, val_node_idx, ChildSide::Left).is_err() {
+                                        return Err("unable to set value as the operand of a unary operator".to_string());
+                                    }
+                                    cursor = val_node_idx;
                                 }
                             }
                         },
@@ -731,125 +2951,99 @@ impl Calculator {
                     }
                 },
                 CalcParseToken::Operator => {
-                    let op_node_idx = Some(tree.add_node(TreeNode::new(
-                        CalcEvalItem::new(token_type, token))));
+                    let op_node_idx = tree.add_node(TreeNode::new(
+                        CalcEvalItem::new(token_type, token)));
                     if cursor.is_none() || !tree.has_root() {
                         return Err("the first token cannot be an operator".to_string());
                     }
                     let cursor_loc = cursor.unwrap();
-                    match token.as_str() {
-                        "*" | "/" => {
-                            let cursor_node = match tree.node_at(cursor_loc) {
-                                Some(n) => n,
-                                None => { return Err("no node exists at cursor index location".to_string()); }
-                            };
-                            match cursor_node.value.token_type {
-                                // the "*/" operators have precedence, so they are inserted at the
-                                //   bottom of the tree (where the cursor is) so that they are
-                                //   processed first ("+-" operators cannot be handled so easily)
-                                // if cursor is at a value or ")", insert the new operation node
-                                //   between the cursor and its parent (and handle special case
-                                //   where cursor is at root)
-                                CalcParseToken::Value | CalcParseToken::CloseParen => {
-                                    if tree.matches_root(cursor_loc) {
-                                        let result = tree.replace_root_with_node(
-                                            op_node_idx.unwrap(), ChildSide::Left);
-                                        if result.is_err() {
-                                            return Err("unable to replace root with operator node".to_string());
-                                        }
-                                        cursor = op_node_idx;
-                                    } else {
-                                        let result = tree.insert_node_above_node(cursor_loc, op_node_idx.unwrap(), ChildSide::Left);
-                                        if result.is_err() {
-                                            return Err("unable to insert new operator node in place of an existing value node".to_string());
-                                        }
-                                        cursor = op_node_idx;
-                                    }
-                                },
-                                CalcParseToken::Operator => {
-                                    // now that the cursor is always set to the last inserted node
-                                    //   (whether it be a value, operator, or paren), it must be an
-                                    //   error if the cursor is at an operator when another operator
-                                    //   is the next token
-                                    return Err("cannot have two consecutive operators: expected a value or open paren".to_string());
-                                },
-                                CalcParseToken::OpenParen => {
-                                    // now that the cursor is always set to the last inserted node
-                                    //   (whether it be a value, operator, or paren), it must be an
-                                    //   error if the cursor is at an open paren when another operator
-                                    //   is the next token
-                                    return Err("cannot have an operator following an open paren: expected a value or open paren".to_string());
+                    let precedence = match CalcParseToken::get_operator_precedence(token) {
+                        Some(p) => p,
+                        None => { return Err(format!("unknown operator [{}]", token)); }
+                    };
+                    let cursor_node = match tree.node_at(cursor_loc) {
+                        Some(n) => n,
+                        None => { return Err("no node exists at cursor index location".to_string()); }
+                    };
+                    match cursor_node.value.token_type {
+                        CalcParseToken::Value | CalcParseToken::CloseParen | CalcParseToken::Identifier | CalcParseToken::Postfix => {
+                            // go back up the tree, parent to parent, until (whichever is first):
+                            //   - root node, or
+                            //   - open paren (e.g. if closed paren that is not root, go up), or
+                            //   - an operator that binds less tightly than the new one
+                            // operators of equal precedence are climbed past so they are
+                            //   evaluated left to right, except for right-associative
+                            //   operators ("^") where the new operator stays below
+                            // after stopping:
+                            //   - if root node, make new op the root, or
+                            //   - otherwise insert new op between the stopping node and its parent
+                            let mut insert_loc = cursor_loc;
+                            while !tree.matches_root(insert_loc) {
+                                let insert_loc_parent_loc = tree.get_node_parent(insert_loc);
+                                if insert_loc_parent_loc.is_none() {
+                                    return Err("a node that isn't the root has no parent".to_string());
                                 }
-                            }
-                        },
-                        "+" | "-" => {
-                            let cursor_node = match tree.node_at(cursor_loc) {
-                                Some(n) => n,
-                                None => { return Err("no node exists at cursor index location".to_string()); }
-                            };
-                            match cursor_node.value.token_type {
-                                CalcParseToken::Value | CalcParseToken::CloseParen => {
-                                    // go back up the tree, parent to parent, until (whichever is first):
-                                    //   - root node, or
-                                    //   - open paren (e.g. if closed paren that is not root, go up)
-                                    // after stopping:
-                                    //   - if root node, make new "+-" op the root, or
-                                    //   - if open paren, insert new "+-" below the paren
-                                    let mut insert_loc = cursor_loc;
-                                    while !tree.matches_root(insert_loc) {
-                                        let insert_loc_parent_loc = tree.get_node_parent(insert_loc);
-                                        if insert_loc_parent_loc.is_none() {
-                                            return Err("a node that isn't the root has no parent".to_string());
-                                        }
-                                        let insert_loc_parent_loc = insert_loc_parent_loc.unwrap();
-                                        let insert_loc_parent = match tree.node_at(insert_loc_parent_loc) {
-                                            Some(n) => n,
-                                            None => { return Err("no node exists at a node's parent's location".to_string()); }
+                                let insert_loc_parent_loc = insert_loc_parent_loc.unwrap();
+                                let insert_loc_parent = match tree.node_at(insert_loc_parent_loc) {
+                                    Some(n) => n,
+                                    None => { return Err("no node exists at a node's parent's location".to_string()); }
+                                };
+                                match insert_loc_parent.value.token_type {
+                                    // if the parent is an "(" then stop here
+                                    CalcParseToken::OpenParen => {
+                                        break;
+                                    },
+                                    CalcParseToken::Operator => {
+                                        let parent_precedence = match CalcParseToken::get_operator_precedence(&insert_loc_parent.value.string_value) {
+                                            Some(p) => p,
+                                            None => { return Err(format!("unknown operator [{}]", insert_loc_parent.value.string_value)); }
                                         };
-                                        match insert_loc_parent.value.token_type {
-                                            // if the parent is an "(" then stop here
-                                            CalcParseToken::OpenParen => {
-                                                break;
-                                            },
-                                            _ => ()
+                                        if parent_precedence < precedence ||
+                                                (parent_precedence == precedence && CalcParseToken::is_right_associative(token)) {
+                                            break;
                                         }
-                                        // if the node is not root and its parent is not a "(", keep going up
-                                        insert_loc = insert_loc_parent_loc;
-                                    }
-                                    // this if/else can almost exactly be copied from above "*/" operator
-                                    //   code for when cursor is at a value node
-                                    if tree.matches_root(insert_loc) {
-                                        let result = tree.replace_root_with_node(
-                                            op_node_idx.unwrap(), ChildSide::Left);
-                                        if result.is_err() {
-                                            return Err("unable to replace root with operator node".to_string());
-                                        }
-                                        cursor = op_node_idx;
-                                    } else {
-                                        let result = tree.insert_node_above_node(insert_loc, op_node_idx.unwrap(), ChildSide::Left);
-                                        if result.is_err() {
-                                            return Err("unable to insert new operator node in place of an existing value node".to_string());
-                                        }
-                                        cursor = op_node_idx;
-                                    }
-                                },
-                                CalcParseToken::Operator => {
-                                    // now that the cursor is always set to the last inserted node
-                                    //   (whether it be a value, operator, or paren), it must be an
-                                    //   error if the cursor is at an operator when another operator
-                                    //   is the next token
-                                    return Err("cannot have two consecutive operators: expected a value or open paren".to_string());
-                                },
-                                CalcParseToken::OpenParen => {
-                                    // now that the cursor is always set to the last inserted node
-                                    //   (whether it be a value, operator, or paren), it must be an
-                                    //   error if the cursor is at an open paren when another operator
-                                    //   is the next token
-                                    return Err("cannot have an operator following an open paren: expected a value or open paren".to_string());
+                                    },
+                                    CalcParseToken::UnaryOperator
+                                            if CalcParseToken::get_unary_operator_precedence(&insert_loc_parent.value.string_value) < precedence => {
+                                        break;
+                                    },
+                                    _ => ()
                                 }
+                                // the parent binds at least as tightly as the new op, keep going up
+                                insert_loc = insert_loc_parent_loc;
                             }
+                            if tree.matches_root(insert_loc) {
+                                let result = tree.replace_root_with_node(
+                                    op_node_idx, ChildSide::Left);
+                                if result.is_err() {
+                                    return Err("unable to replace root with operator node".to_string());
+                                }
+                                cursor = Some(op_node_idx);
+                            } else {
+                                let result = tree.insert_node_above_node(insert_loc, op_node_idx, ChildSide::Left);
+                                if result.is_err() {
+                                    return Err("unable to insert new operator node in place of an existing value node".to_string());
+                                }
+                                cursor = Some(op_node_idx);
+                            }
+                        },
+                        CalcParseToken::Operator => {
+                            // now that the cursor is always set to the last inserted node
+                            //   (whether it be a value, operator, or paren), it must be an
+                            //   error if the cursor is at an operator when another operator
+                            //   is the next token
+                            return Err("cannot have two consecutive operators: expected a value or open paren".to_string());
                         },
-                        _ => { return Err(format!("unknown operator [{}]", token)); }
+                        CalcParseToken::OpenParen => {
+                            // now that the cursor is always set to the last inserted node
+                            //   (whether it be a value, operator, or paren), it must be an
+                            //   error if the cursor is at an open paren when another operator
+                            //   is the next token
+                            return Err("cannot have an operator following an open paren: expected a value or open paren".to_string());
+                        },
+                        CalcParseToken::UnaryOperator => {
+                            return Err(format!("cannot have an operator following [{}]: expected a value or open paren", cursor_node.value.string_value));
+                        }
                     }
                 },
                 CalcParseToken::OpenParen => {
@@ -889,6 +3083,28 @@ impl Calculator {
                                 },
                                 CalcParseToken::CloseParen => {
                                     return Err("a close paren cannot immediately be followed by an open paren".to_string());
+                                },
+                                CalcParseToken::Postfix => {
+                                    return Err("a percent sign cannot immediately be followed by an open paren".to_string());
+                                },
+                                CalcParseToken::UnaryOperator => {
+                                    if cursor_node.has_left() {
+                                        return Err("the previous token was a unary operator, which should not already have an operand".to_string());
+                                    }
+                                    if tree.set_node_child(c, paren_node_idx, ChildSide::Left).is_err() {
+                                        return Err("unable to set open paren as the operand of a unary operator".to_string());
+                                    }
+                                    cursor = paren_node_idx;
+                                },
+                                CalcParseToken::Identifier => {
+                                    // the paren holds the function's argument
+                                    if cursor_node.has_left() {
+                                        return Err("a function call cannot immediately be followed by an open paren".to_string());
+                                    }
+                                    if tree.set_node_child(c, paren_node_idx, ChildSide::Left).is_err() {
+                                        return Err("unable to set open paren as the argument of a function".to_string());
+                                    }
+                                    cursor = paren_node_idx;
                                 }
                             }
                         },
@@ -898,730 +3114,3277 @@ impl Calculator {
                         }
                     }
                 },
+                // a unary operator is placed in the tree like an open paren, and its
+                //   operand becomes its left child
+                CalcParseToken::UnaryOperator => {
+                    let unary_node_idx = Some(tree.add_node(TreeNode::new(
+                        CalcEvalItem::new(token_type, token))));
+                    match cursor {
+                        Some(c) => {
+                            let cursor_node = match tree.node_at_mut(c) {
+                                Some(n) => n,
+                                None => { return Err("no node exists at cursor index location".to_string()); }
+                            };
+                            match cursor_node.value.token_type {
+                                CalcParseToken::Operator => {
+                                    if !cursor_node.has_left() {
+                                        return Err("the previous token was an operator, which should already have a left-hand side operand".to_string());
+                                    }
+                                    if tree.set_node_child(c, unary_node_idx, ChildSide::Right).is_err() {
+                                        return Err("unable to set unary operator as right child of an operator".to_string());
+                                    }
+                                },
+                                CalcParseToken::OpenParen | CalcParseToken::UnaryOperator => {
+                                    if cursor_node.has_left() {
+                                        return Err("the previous token should not already have any child nodes".to_string());
+                                    }
+                                    if tree.set_node_child(c, unary_node_idx, ChildSide::Left).is_err() {
+                                        return Err("unable to set unary operator as the operand of the previous token".to_string());
+                                    }
+                                },
+                                CalcParseToken::Value | CalcParseToken::CloseParen | CalcParseToken::Identifier | CalcParseToken::Postfix => {
+                                    return Err(format!("[{}] cannot follow a value: it only takes an operand on its right", token));
+                                }
+                            }
+                            cursor = unary_node_idx;
+                        },
+                        None => {
+                            cursor = unary_node_idx;
+                            tree.set_root(unary_node_idx);
+                        }
+                    }
+                },
+                CalcParseToken::Postfix => {
+                    let percent_node_idx = tree.add_node(TreeNode::new(
+                        CalcEvalItem::new(token_type, token)));
+                    if cursor.is_none() || !tree.has_root() {
+                        return Err("the first token cannot be a percent sign".to_string());
+                    }
+                    let cursor_loc = cursor.unwrap();
+                    let cursor_node = match tree.node_at(cursor_loc) {
+                        Some(n) => n,
+                        None => { return Err("no node exists at cursor index location".to_string()); }
+                    };
+                    match cursor_node.value.token_type {
+                        // "n!!" is usually the double factorial, so a second "!" is
+                        //   refused rather than taken as "(n!)!"
+                        CalcParseToken::Postfix if token == "!" && cursor_node.value.string_value == "!" => {
+                            return Err("[n!!] would be a double factorial, which isn't supported: write (n!)! for the factorial of a factorial".to_string());
+                        },
+                        // the percent sign binds more tightly than any operator, and only
+                        //   applies to the value, paren group, or function call right
+                        //   before it, so it goes directly above that node
+                        CalcParseToken::Value | CalcParseToken::CloseParen | CalcParseToken::Identifier | CalcParseToken::Postfix => {
+                            if tree.matches_root(cursor_loc) {
+                                if tree.replace_root_with_node(percent_node_idx, ChildSide::Left).is_err() {
+                                    return Err("unable to replace root with percent node".to_string());
+                                }
+                            } else if tree.insert_node_above_node(cursor_loc, percent_node_idx, ChildSide::Left).is_err() {
+                                return Err("unable to insert percent node above its value".to_string());
+                            }
+                            cursor = Some(percent_node_idx);
+                        },
+                        CalcParseToken::Operator | CalcParseToken::OpenParen | CalcParseToken::UnaryOperator => {
+                            return Err("a percent sign must follow a value".to_string());
+                        }
+                    }
+                },
                 CalcParseToken::CloseParen => {
                     // first stab at this:
                     // go back up tree until first "(", then check that:
                     //   - all descendant operators have 2 children, and
                     //   - all descendant parens are ")", not "("
 
-                    // can we just go up parent-to-parent?  or must we re-descend down the
-                    //   tree with a traversal?
+                    // can we just go up parent-to-parent?  or must we re-descend down the
+                    //   tree with a traversal?
+
+                    if cursor.is_none() || !tree.has_root() {
+                        return Err("the first token cannot be a closed paren".to_string());
+                    }
+                    let cursor_loc = cursor.unwrap();
+                    if let Some(n) = tree.node_at(cursor_loc) {
+                        if n.value.token_type == CalcParseToken::UnaryOperator && !n.has_left() {
+                            return Err(format!("close paren not expected because [{}] does not have an operand", n.value.string_value));
+                        }
+                    }
+
+                    // this is the "go up then traverse down" approach
+                    // let mut open_paren_loc = cursor_loc;
+                    // while !tree.matches_root(open_paren_loc) {
+                    //     let open_paren_loc_parent_loc = tree.get_node_parent(open_paren_loc);
+                    //     if open_paren_loc_parent_loc.is_none() {
+                    //         return Err("a node that isn't the root has no parent");
+                    //     }
+                    //     open_paren_loc = open_paren_loc_parent_loc.unwrap();
+                    //     let open_paren_node = match tree.node_at(open_paren_loc) {
+                    //         Some(n) => n,
+                    //         None => { return Err("no node exists at a node's parent's location"); }
+                    //     };
+                    //     match open_paren_node.value.token_type {
+                    //         // if the parent is an "(" then stop here
+                    //         CalcParseToken::OpenParen => {
+                    //             break;
+                    //         },
+                    //         _ => ()
+                    //     }
+                    // }
+
+                    // let open_paren_node = match tree.node_at(open_paren_loc) {
+                    //     Some(n) => n,
+                    //     None => { return Err("no node exists at found nearest open paren"); }
+                    // };
+                    // match open_paren_node.value.token_type {
+                    //     CalcParseToken::OpenParen => {
+                    //         // do traversal starting at open_paren_loc
+                    //     },
+                    //     _ => {
+                    //         // handle the case where we hit the root before finding an open paren
+                    //         return Err("no corresponding open paren token found for the new close paren");
+                    //     }
+                    // }
+
+                    // this is the "just go up" approach
+                    let mut reverse_cursor_loc = cursor_loc;
+                    while !tree.matches_root(reverse_cursor_loc) {
+                        let parent_loc = tree.get_node_parent(reverse_cursor_loc);
+                        if parent_loc.is_none() {
+                            return Err("a node that isn't the root has no parent".to_string());
+                        }
+                        reverse_cursor_loc = parent_loc.unwrap();
+                        let open_paren_node = match tree.node_at(reverse_cursor_loc) {
+                            Some(n) => n,
+                            None => { return Err("no node exists at a node's parent's location".to_string()); }
+                        };
+                        match open_paren_node.value.token_type {
+                            // if the parent is an "(" then stop here
+                            CalcParseToken::OpenParen => {
+                                break;
+                            },
+                            CalcParseToken::Operator => {
+                                if !open_paren_node.has_left() || !open_paren_node.has_right() {
+                                    return Err("close paren not expected because previous operator node does not have two operands".to_string());
+                                }
+                            },
+                            CalcParseToken::UnaryOperator => {
+                                if !open_paren_node.has_left() {
+                                    return Err("close paren not expected because previous unary operator node does not have an operand".to_string());
+                                }
+                            },
+                            CalcParseToken::Value | CalcParseToken::CloseParen | CalcParseToken::Identifier | CalcParseToken::Postfix => ()
+                        }
+                    }
+                    let open_paren_node = match tree.node_at_mut(reverse_cursor_loc) {
+                        Some(n) => n,
+                        None => { return Err("no node exists at found nearest open paren".to_string()); }
+                    };
+                    match open_paren_node.value.token_type {
+                        CalcParseToken::OpenParen => {
+                            // change open paren to a close paren
+                            open_paren_node.value = CalcEvalItem::new(token_type, token);
+                            cursor = Some(reverse_cursor_loc);
+                            // if the paren holds a function's argument, the function call
+                            //   is now complete and acts like a single value
+                            if let Some(parent_loc) = tree.get_node_parent(reverse_cursor_loc) {
+                                if let Some(parent) = tree.node_at(parent_loc) {
+                                    if parent.value.token_type == CalcParseToken::Identifier {
+                                        cursor = Some(parent_loc);
+                                    }
+                                }
+                            }
+                        },
+                        _ => {
+                            // handle the case where we hit the root before finding an open paren
+                            return Err("no corresponding open paren token found for the new close paren".to_string());
+                        }
+                    }
+                }
+            }
+        }
+        Ok(tree)
+    }
+
+    fn is_expecting_operand(tree: &Tree<CalcEvalItem>, cursor: Option<NodeIndex>) -> bool {
+        match cursor.and_then(|c| tree.node_at(c)) {
+            Some(n) => matches!(n.value.token_type,
+                CalcParseToken::Operator | CalcParseToken::OpenParen | CalcParseToken::UnaryOperator),
+            None => true
+        }
+    }
+
+    // a percentage in parens is still a percentage, so "200 + (15%)" is 230
+    fn is_percent_node(tree: &Tree<CalcEvalItem>, node_loc: Option<NodeIndex>) -> bool {
+        let mut node = node_loc.and_then(|loc| tree.node_at(loc));
+        while let Some(n) = node.filter(|n| n.value.token_type == CalcParseToken::CloseParen) {
+            node = n.get_left().and_then(|l| tree.node_at(l));
+        }
+        match node {
+            Some(n) => n.value.token_type == CalcParseToken::Postfix && n.value.string_value == "%",
+            None => false
+        }
+    }
+
+    // the arguments of a function call are the chain of "," operators
+    //   under the function's paren -- since "," is evaluated left to right,
+    //   each extra argument adds another "," to the left side of the chain
+    fn count_function_arguments(tree: &Tree<CalcEvalItem>, function_loc: NodeIndex) -> usize {
+        let mut arg_count = 1;
+        let mut node_loc = tree.node_at(function_loc)
+            .and_then(|f| f.get_left())
+            .and_then(|paren_loc| tree.node_at(paren_loc))
+            .and_then(|paren| paren.get_left());
+        while let Some(loc) = node_loc {
+            match tree.node_at(loc) {
+                Some(n) if n.value.token_type == CalcParseToken::Operator && n.value.string_value == "," => {
+                    arg_count += 1;
+                    node_loc = n.get_left();
+                },
+                _ => { break; }
+            }
+        }
+        arg_count
+    }
+
+    // results are kept as a BigDecimal, and this is just for comparing them
+    //   in tests
+    #[cfg(test)]
+    fn evaluate_calc(&self, calc: &str) -> Result<String, String> {
+        match self.evaluate_calc_value(calc) {
+            Ok(x) => Ok(x.to_known_digits(self.rounding_mode).to_string()),
+            Err(m) => Err(m)
+        }
+    }
+
+    fn evaluate_calc_value(&self, calc: &str) -> Result<CalcValue, String> {
+        let tree = match Calculator::build_calc_eval_tree(calc) {
+            Ok(t) => t,
+            Err(m) => { return Err(m); }
+        };
+        self.evaluate_tree(&tree, &BTreeMap::new(), 0, &mut CalcEvalState::new(None))
+    }
+
+    // anything that can't be kept exact, like a constant, a function, or a
+    //   fractional power, is an error
+    #[cfg(test)]
+    fn evaluate_calc_exact(&self, calc: &str) -> Result<Rational, String> {
+        match self.evaluate_calc_value(calc) {
+            Ok(CalcValue::Exact(x)) => Ok(x),
+            Ok(CalcValue::Inexact(_, m)) => Err(m),
+            Err(m) => Err(m)
+        }
+    }
+
+    // constants and functions like sqrt() can't be kept exact, so in rational
+    //   mode calcs that use them fall back to the usual decimals -- but a calc
+    //   that asks for a fraction itself, like "1/3 * pi as fraction", is
+    //   told why it can't have one
+    fn perform_calc_eval_exact(&self, calc: &str, format: CalcFractionFormat, is_requested: bool) -> CalcResult {
+        let value = match self.evaluate_calc_value(calc) {
+            Ok(x) => x,
+            Err(m) => { return CalcResult::Error(m); }
+        };
+        // a factorization is shown as its factors, and a comparison as true or
+        //   false, however fractions are shown
+        if Calculator::is_whole_call(calc, "factor") || Calculator::is_boolean_expression(calc) {
+            return self.finish_result(calc, value);
+        }
+        match value {
+            CalcValue::Exact(x) => match format {
+                CalcFractionFormat::Decimal if self.precision.is_some() => {
+                    self.round_result(CalcResult::Decimal(x.to_bigdecimal(self.rounding_mode)))
+                },
+                _ => CalcResult::Fraction(x, format)
+            },
+            CalcValue::Inexact(_, m) if is_requested && format != CalcFractionFormat::Decimal => CalcResult::Error(m),
+            _ => self.finish_result(calc, value)
+        }
+    }
+
+    const MAX_LITERAL_EXPONENT: i64 = 10_000;
+
+    // a repeating decimal is cut short here, but not by parse_value_exact()
+    fn parse_value(value: &str) -> Result<BigDecimal, String> {
+        if let Some(x) = CalcParseToken::parse_repeating_decimal(value) {
+            return Ok(x.to_bigdecimal(RoundingMode::HalfEven));
+        }
+        if let Some(x) = CalcParseToken::parse_base_literal(value) {
+            return Ok(BigDecimal::from(x));
+        }
+        match BigDecimal::from_str(value) {
+            Ok(x) => {
+                // "1e999999999" would otherwise be written out in full
+                if x.as_bigint_and_exponent().1.abs() > Calculator::MAX_LITERAL_EXPONENT {
+                    return Err(format!("the exponent of value [{}] is too large", value));
+                }
+                Ok(x)
+            },
+            Err(_) => Err(format!("Unable to parse value [{}] into a BigDecimal", value))
+        }
+    }
+
+    fn parse_value_exact(value: &str) -> Result<Rational, String> {
+        if let Some(x) = CalcParseToken::parse_repeating_decimal(value) {
+            return Ok(x);
+        }
+        match Calculator::parse_value(value) {
+            Ok(x) => Ok(Rational::from_bigdecimal(&x)),
+            Err(m) => Err(m)
+        }
+    }
+
+    // a function that calls itself would otherwise never finish
+    const MAX_FUNCTION_CALL_DEPTH: usize = 100;
+
+    // a function that calls itself more than once, like
+    //   "f(n) = if(n <= 0, 1, f(n-1) + f(n-1))", doubles its calls with each
+    //   level, so it can take forever without ever nesting too deeply
+    const MAX_FUNCTION_CALLS: usize = 10_000;
+
+    // arguments are the values of the parameters of the user-defined function
+    //   being evaluated, depth is the number of nested calls to user-defined
+    //   functions, and state is shared with those calls
+    fn evaluate_tree(&self, tree: &Tree<CalcEvalItem>, arguments: &BTreeMap<String, CalcValue>, depth: usize,
+            state: &mut CalcEvalState) -> Result<CalcValue, String> {
+        let mut eval_stack = Vec::<CalcValue>::new();
+        let conditional_branches = Calculator::find_conditional_branches(tree);
+        let mut postorder = PostOrderIter::new(tree);
+        let mut last_index: Option<NodeIndex> = None;
+        loop {
+            // in programmer mode, the value just calculated is wrapped around
+            //   to fit the word
+            if let (Some(word_size), Some(i)) = (state.word_size, last_index) {
+                if let Some(top) = eval_stack.last_mut().filter(|x| x.is_integer() && !Calculator::is_negated_value(tree, i)) {
+                    match bigmath::wrap_to_word(&top.to_bigdecimal(self.rounding_mode), word_size.bits, word_size.signed) {
+                        Ok((wrapped, overflowed)) => {
+                            *top = top.with_integer(wrapped);
+                            state.overflowed |= overflowed;
+                        },
+                        Err(m) => { return Err(m); }
+                    }
+                }
+            }
+            // the condition of an "if" is on top of the stack once it's calculated
+            if let Some((true_loc, false_loc)) = last_index.and_then(|i| conditional_branches.get(&i)) {
+                let is_true = eval_stack.last().is_some_and(|x| !x.is_zero());
+                postorder.skip_subtree(if is_true { *false_loc } else { *true_loc });
+            }
+            let index = match postorder.next() {
+                Some(i) => i,
+                None => { break; }
+            };
+            last_index = Some(index);
+            let node = match tree.node_at(index) {
+                Some(n) => n,
+                None => { return Err("Node does not exist at given index".to_string()); }
+            };
+            // the tree tells a prefix "-" apart from a subtraction
+            let token_type = node.value.token_type;
+            if token_type == CalcParseToken::Value {
+                match Calculator::parse_value_exact(&node.value.string_value) {
+                    Ok(x) => { eval_stack.push(CalcValue::Exact(x)); },
+                    Err(m) => { return Err(m); }
+                }
+            } else if token_type == CalcParseToken::Operator {
+                // commas only separate function arguments, which are each left on
+                //   the stack for the function to use
+                if node.value.string_value == "," {
+                    continue;
+                }
+                if eval_stack.len() < 2 {
+                    return Err(format!("cannot perform operation [{}] with only one value on the stack", &node.value.string_value));
+                }
+                let operands = eval_stack.split_off(eval_stack.len() - 2);
+                let result = CalcValue::apply(&operands, self.rounding_mode,
+                    |args| Calculator::apply_exact_operation(tree, node, &args[0], &args[1]),
+                    |args| Calculator::apply_decimal_operation(tree, node, &args[0], &args[1], self.rounding_mode));
+                match result {
+                    Ok(x) => { eval_stack.push(x); },
+                    Err(m) => { return Err(m); }
+                }
+            } else if token_type == CalcParseToken::UnaryOperator {
+                let value = match eval_stack.pop() {
+                    Some(x) => x,
+                    None => { return Err(format!("[{}] is missing its operand", &node.value.string_value)); }
+                };
+                let operator = node.value.string_value.as_str();
+                let result = CalcValue::apply(&[value], self.rounding_mode,
+                    |args| match operator {
+                        "~" => Calculator::apply_integer_operation(&args[0], &args[0], |a| bigmath::bit_not(&a[0])),
+                        "-" => Ok(args[0].neg()),
+                        "+" => Ok(args[0].clone()),
+                        "not" => Ok(Calculator::boolean_to_rational(args[0].numer().is_zero())),
+                        _ => Err(format!("cannot perform unknown operation [{}]", operator))
+                    },
+                    |args| match operator {
+                        "~" => bigmath::bit_not(&args[0]),
+                        "-" => Ok(-&args[0]),
+                        "+" => Ok(args[0].clone()),
+                        "not" => Ok(Calculator::boolean_to_bigdecimal(args[0].is_zero())),
+                        _ => Err(format!("cannot perform unknown operation [{}]", operator))
+                    });
+                match result {
+                    Ok(x) => { eval_stack.push(x); },
+                    Err(m) => { return Err(m); }
+                }
+            } else if token_type == CalcParseToken::Postfix {
+                let value = match eval_stack.pop() {
+                    Some(x) => x,
+                    None => { return Err(format!("[{}] must follow a value", &node.value.string_value)); }
+                };
+                let operator = node.value.string_value.as_str();
+                let result = CalcValue::apply(&[value], self.rounding_mode,
+                    |args| match operator {
+                        "%" => Ok(args[0].mul(&Rational::new(BigInt::from(1), BigInt::from(100)).unwrap())),
+                        "!" => Calculator::apply_integer_operation(&args[0], &args[0], |a| bigmath::factorial(&a[0])),
+                        "°" => match self.get_angle_unit_size_exact(AngleUnit::Degrees) {
+                            Ok(size) => Ok(args[0].mul(&size)),
+                            Err(m) => Err(m)
+                        },
+                        _ => Err(format!("cannot perform unknown operation [{}]", operator))
+                    },
+                    |args| match operator {
+                        "%" => Ok(&args[0] / BigDecimal::from(100)),
+                        "!" => bigmath::factorial(&args[0]),
+                        "°" => Ok(&args[0] * self.get_angle_unit_size(AngleUnit::Degrees)),
+                        _ => Err(format!("cannot perform unknown operation [{}]", operator))
+                    });
+                match result {
+                    Ok(x) => { eval_stack.push(x); },
+                    Err(m) => { return Err(m); }
+                }
+            } else if token_type == CalcParseToken::Identifier {
+                let name = &node.value.string_value;
+                // an identifier without an argument list is a variable
+                if !node.has_left() {
+                    match self.get_named_value(name, arguments) {
+                        Ok(x) => { eval_stack.push(x); },
+                        Err(m) => { return Err(m); }
+                    }
+                    continue;
+                }
+                let arg_count = Calculator::count_function_arguments(tree, index);
+                if name == Calculator::CONDITIONAL_FUNCTION {
+                    match Calculator::take_conditional_result(&mut eval_stack, arg_count) {
+                        Ok(x) => { eval_stack.push(x); },
+                        Err(m) => { return Err(m); }
+                    }
+                    continue;
+                }
+                if let Some(function) = self.user_functions.get(name) {
+                    if arg_count != function.params.len() {
+                        return Err(format!("function [{}] takes {} argument(s), not {}", name, function.params.len(), arg_count));
+                    }
+                    if eval_stack.len() < arg_count {
+                        return Err(format!("function [{}] is missing its argument", name));
+                    }
+                    if depth >= Calculator::MAX_FUNCTION_CALL_DEPTH {
+                        return Err(format!("more than {} nested function calls: does [{}] call itself?", Calculator::MAX_FUNCTION_CALL_DEPTH, name));
+                    }
+                    state.function_calls += 1;
+                    if state.function_calls > Calculator::MAX_FUNCTION_CALLS {
+                        return Err(format!("more than {} function calls: does [{}] call itself too often?", Calculator::MAX_FUNCTION_CALLS, name));
+                    }
+                    let args = eval_stack.split_off(eval_stack.len() - arg_count);
+                    let function_arguments: BTreeMap<String, CalcValue> =
+                        function.params.iter().cloned().zip(args).collect();
+                    match self.evaluate_tree(&function.tree, &function_arguments, depth + 1, state) {
+                        Ok(x) => { eval_stack.push(x); },
+                        Err(m) => { return Err(m); }
+                    }
+                    continue;
+                }
+                if !Calculator::is_function_name(name) {
+                    return Err(format!("unknown function [{}]", name));
+                }
+                if eval_stack.len() < arg_count {
+                    return Err(format!("function [{}] is missing its argument", name));
+                }
+                let args = eval_stack.split_off(eval_stack.len() - arg_count);
+                let result = CalcValue::apply(&args, self.rounding_mode,
+                    |args| Calculator::apply_exact_function(name, args),
+                    |args| self.apply_decimal_function(name, args));
+                match result {
+                    Ok(x) => { eval_stack.push(x); },
+                    Err(m) => { return Err(m); }
+                }
+            }
+        }
+        if eval_stack.len() > 1 {
+            return Err("Extraneous value(s) remain on the stack after perfoming the evaluation".to_string());
+        }
+        if eval_stack.is_empty() {
+            return Err("No final result value is on the stack after perfoming the evaluation".to_string());
+        }
+        Ok(eval_stack.pop().unwrap())
+    }
+
+    // a variable, a constant, an angle unit, a previous result, or a parameter
+    //   of the user-defined function being evaluated
+    fn get_named_value(&self, name: &str, arguments: &BTreeMap<String, CalcValue>) -> Result<CalcValue, String> {
+        if let Some(x) = arguments.get(name) {
+            return Ok(x.clone());
+        }
+        // fractions are kept exact, and decimals are exactly a fraction anyway
+        let value = if CalcParseToken::is_history_reference(name) {
+            self.get_history_result(name, Calculator::history_result_to_rational)
+        } else if name == Calculator::LAST_RESULT_NAME {
+            self.get_last_result(Calculator::history_result_to_rational)
+        } else if let Some(x) = Calculator::get_constant(name) {
+            return Ok(CalcValue::Inexact(x, format!("constant [{}] cannot be calculated exactly", name)));
+        } else if let Some(unit) = Calculator::get_angle_unit(name) {
+            return match self.get_angle_unit_size_exact(unit) {
+                Ok(x) => Ok(CalcValue::Exact(x)),
+                Err(m) => Ok(CalcValue::Inexact(self.get_angle_unit_size(unit), m))
+            };
+        } else if let Some(x) = self.exact_variables.get(name) {
+            Ok(x.clone())
+        } else {
+            match self.variables.get(name) {
+                Some(x) => Ok(Rational::from_bigdecimal(x)),
+                None => Err(format!("unknown name [{}]", name))
+            }
+        };
+        match value {
+            Ok(x) => Ok(CalcValue::Exact(x)),
+            Err(m) => Err(m)
+        }
+    }
+
+    // the decimals of the two sides of a comparison can be rounded
+    //   differently, so "1/3 + 1/3 == 2/3" is compared as exact fractions
+    fn apply_exact_operation(tree: &Tree<CalcEvalItem>, node: &TreeNode<CalcEvalItem>, val_left: &Rational, val_right: &Rational) -> Result<Rational, String> {
+        match node.value.string_value.as_str() {
+            "+" if Calculator::is_percent_node(tree, node.get_right()) => Ok(val_left.add(&val_left.mul(val_right))),
+            "-" if Calculator::is_percent_node(tree, node.get_right()) => Ok(val_left.sub(&val_left.mul(val_right))),
+            "+" => Ok(val_left.add(val_right)),
+            "-" => Ok(val_left.sub(val_right)),
+            "*" => Ok(val_left.mul(val_right)),
+            "/" => val_left.div(val_right),
+            "//" => val_left.floor_div(val_right),
+            "mod" => val_left.floor_mod(val_right),
+            "^" => val_left.pow(val_right),
+            "&" => Calculator::apply_integer_operation(val_left, val_right, |args| bigmath::bit_and(&args[0], &args[1])),
+            "|" => Calculator::apply_integer_operation(val_left, val_right, |args| bigmath::bit_or(&args[0], &args[1])),
+            "xor" => Calculator::apply_integer_operation(val_left, val_right, |args| bigmath::bit_xor(&args[0], &args[1])),
+            "<<" => Calculator::apply_integer_operation(val_left, val_right, |args| bigmath::shift_left(&args[0], &args[1])),
+            ">>" => Calculator::apply_integer_operation(val_left, val_right, |args| bigmath::shift_right(&args[0], &args[1])),
+            "of" if Calculator::is_percent_node(tree, node.get_left()) => Ok(val_left.mul(val_right)),
+            "of" => Err("[of] must follow a percentage, like \"15% of 80\"".to_string()),
+            "<" => Ok(Calculator::boolean_to_rational(val_left < val_right)),
+            "<=" => Ok(Calculator::boolean_to_rational(val_left <= val_right)),
+            ">" => Ok(Calculator::boolean_to_rational(val_left > val_right)),
+            ">=" => Ok(Calculator::boolean_to_rational(val_left >= val_right)),
+            "==" => Ok(Calculator::boolean_to_rational(val_left == val_right)),
+            "!=" => Ok(Calculator::boolean_to_rational(val_left != val_right)),
+            "and" => Ok(Calculator::boolean_to_rational(!val_left.numer().is_zero() && !val_right.numer().is_zero())),
+            "or" => Ok(Calculator::boolean_to_rational(!val_left.numer().is_zero() || !val_right.numer().is_zero())),
+            _ => Err(format!("cannot perform unknown operation [{}]", &node.value.string_value))
+        }
+    }
+
+    fn apply_decimal_operation(tree: &Tree<CalcEvalItem>, node: &TreeNode<CalcEvalItem>, val_left: &BigDecimal, val_right: &BigDecimal,
+            rounding_mode: RoundingMode) -> Result<BigDecimal, String> {
+        let result = match node.value.string_value.as_str() {
+            // like a handheld calculator, "200 + 15%" adds 15% of 200, and
+            //   "200 - 15%" subtracts 15% of 200
+            "+" if Calculator::is_percent_node(tree, node.get_right()) => {
+                val_left + val_left * val_right
+            },
+            "-" if Calculator::is_percent_node(tree, node.get_right()) => {
+                val_left - val_left * val_right
+            },
+            "+" => val_left + val_right,
+            "-" => val_left - val_right,
+            "*" => val_left * val_right,
+            "/" => { return bigmath::divide(val_left, val_right, rounding_mode); },
+            "//" => { return bigmath::floor_div(val_left, val_right); },
+            "mod" => { return bigmath::floor_mod(val_left, val_right); },
+            "^" => { return bigmath::pow(val_left, val_right); },
+            "&" => { return bigmath::bit_and(val_left, val_right); },
+            "|" => { return bigmath::bit_or(val_left, val_right); },
+            "xor" => { return bigmath::bit_xor(val_left, val_right); },
+            "<<" => { return bigmath::shift_left(val_left, val_right); },
+            ">>" => { return bigmath::shift_right(val_left, val_right); },
+            "of" => {
+                if !Calculator::is_percent_node(tree, node.get_left()) {
+                    return Err("[of] must follow a percentage, like \"15% of 80\"".to_string());
+                }
+                val_left * val_right
+            },
+            "<" => Calculator::boolean_to_bigdecimal(val_left < val_right),
+            "<=" => Calculator::boolean_to_bigdecimal(val_left <= val_right),
+            ">" => Calculator::boolean_to_bigdecimal(val_left > val_right),
+            ">=" => Calculator::boolean_to_bigdecimal(val_left >= val_right),
+            "==" => Calculator::boolean_to_bigdecimal(val_left == val_right),
+            "!=" => Calculator::boolean_to_bigdecimal(val_left != val_right),
+            // any value other than 0 is true
+            "and" => Calculator::boolean_to_bigdecimal(!val_left.is_zero() && !val_right.is_zero()),
+            "or" => Calculator::boolean_to_bigdecimal(!val_left.is_zero() || !val_right.is_zero()),
+            _ => { return Err(format!("cannot perform unknown operation [{}]", &node.value.string_value)); }
+        };
+        Ok(result)
+    }
+
+    // an approximation of exact arguments is exact too, as are the integer
+    //   functions -- any other function isn't
+    fn apply_exact_function(name: &str, args: &[Rational]) -> Result<Rational, String> {
+        if name == "approx" && args.len() == 2 {
+            args[0].approximate(&args[1])
+        } else if Calculator::INTEGER_FUNCTIONS.contains(&name) {
+            Calculator::apply_integer_function(name, args)
+        } else {
+            Err(format!("function [{}] cannot be calculated exactly", name))
+        }
+    }
+
+    fn apply_decimal_function(&self, name: &str, args: &[BigDecimal]) -> Result<BigDecimal, String> {
+        let arg_count = args.len();
+        if arg_count == 1 && Calculator::get_angle_function(name).is_some() {
+            Calculator::get_angle_function(name).unwrap()(&args[0], self.angle_mode)
+        } else if arg_count == 1 && Calculator::get_unary_function(name).is_some() {
+            Calculator::get_unary_function(name).unwrap()(&args[0])
+        } else if name == "atan2" && arg_count == 2 {
+            bigmath::atan2_in(args, self.angle_mode)
+        } else {
+            match Calculator::get_multi_arg_function(name, arg_count) {
+                Some(f) => f(args),
+                None => Err(format!("function [{}] cannot take {} argument(s)", name, arg_count))
+            }
+        }
+    }
+
+    // "-128" fits in an i8 even though "128" doesn't, so a value isn't
+    //   wrapped until it's negated -- and neither is "-(128)"
+    fn is_negated_value(tree: &Tree<CalcEvalItem>, index: NodeIndex) -> bool {
+        let mut node = tree.node_at(index);
+        while let Some(n) = node.filter(|n| n.value.token_type == CalcParseToken::CloseParen) {
+            node = n.get_left().and_then(|l| tree.node_at(l));
+        }
+        if !node.is_some_and(|n| n.value.token_type == CalcParseToken::Value) {
+            return false;
+        }
+        let mut parent = tree.get_node_parent(index);
+        while let Some(p) = parent.filter(|p| tree.node_at(*p).is_some_and(|n| n.value.token_type == CalcParseToken::CloseParen)) {
+            parent = tree.get_node_parent(p);
+        }
+        parent.and_then(|p| tree.node_at(p))
+            .is_some_and(|p| p.value.token_type == CalcParseToken::UnaryOperator && p.value.string_value == "-")
+    }
+
+    fn boolean_to_bigdecimal(b: bool) -> BigDecimal {
+        BigDecimal::from(u8::from(b))
+    }
+
+    fn boolean_to_rational(b: bool) -> Rational {
+        Rational::from_integer(BigInt::from(u8::from(b)))
+    }
+
+    // the condition of each "if(condition, a, b)" in the tree, along with
+    //   where a and b are -- once the condition is calculated, the branch
+    //   that isn't taken is skipped, so "if(x == 0, 0, 1/x)" doesn't divide
+    //   by zero, and a function can call itself until a condition stops it
+    fn find_conditional_branches(tree: &Tree<CalcEvalItem>) -> BTreeMap<NodeIndex, (NodeIndex, NodeIndex)> {
+        let mut branches = BTreeMap::new();
+        let comma_at = |loc: Option<NodeIndex>| loc.and_then(|l| tree.node_at(l))
+            .filter(|n| n.value.token_type == CalcParseToken::Operator && n.value.string_value == ",");
+        for index in PostOrderIter::new(tree) {
+            let node = match tree.node_at(index) {
+                Some(n) => n,
+                None => { continue; }
+            };
+            if node.value.token_type != CalcParseToken::Identifier || node.value.string_value != Calculator::CONDITIONAL_FUNCTION {
+                continue;
+            }
+            // the arguments are "(condition, a), b" under the function's paren
+            let outer = comma_at(node.get_left().and_then(|p| tree.node_at(p)).and_then(|p| p.get_left()));
+            let inner = comma_at(outer.and_then(|o| o.get_left()));
+            if let (Some(outer), Some(inner)) = (outer, inner) {
+                if let (Some(condition), Some(a), Some(b)) = (inner.get_left(), inner.get_right(), outer.get_right()) {
+                    branches.insert(condition, (a, b));
+                }
+            }
+        }
+        branches
+    }
+
+    // with the branch that wasn't taken skipped, the one that was is on top
+    //   of the stack, right above the condition
+    fn take_conditional_result<T>(eval_stack: &mut Vec<T>, arg_count: usize) -> Result<T, String> {
+        if arg_count != 3 {
+            return Err(format!("function [{}] takes a condition, its value if true, and its value if false, not {} argument(s)", Calculator::CONDITIONAL_FUNCTION, arg_count));
+        }
+        if eval_stack.len() < 2 {
+            return Err(format!("function [{}] is missing its argument", Calculator::CONDITIONAL_FUNCTION));
+        }
+        let value = eval_stack.pop().unwrap();
+        eval_stack.pop();
+        Ok(value)
+    }
+
+    // the bitwise operators only take integers, which a BigDecimal holds
+    //   exactly too
+    fn apply_integer_operation(a: &Rational, b: &Rational, operation: CalcMultiArgFunction) -> Result<Rational, String> {
+        let args = [a.to_bigdecimal(RoundingMode::HalfEven), b.to_bigdecimal(RoundingMode::HalfEven)];
+        match operation(&args) {
+            Ok(x) => Ok(Rational::from_bigdecimal(&x)),
+            Err(m) => Err(m)
+        }
+    }
+
+    // an integer is held exactly by a BigDecimal, and any other argument is
+    //   refused by the function
+    fn apply_integer_function(name: &str, args: &[Rational]) -> Result<Rational, String> {
+        let decimal_args: Vec<BigDecimal> = args.iter().map(|a| a.to_bigdecimal(RoundingMode::HalfEven)).collect();
+        let result = if decimal_args.len() == 1 && Calculator::get_unary_function(name).is_some() {
+            Calculator::get_unary_function(name).unwrap()(&decimal_args[0])
+        } else {
+            match Calculator::get_multi_arg_function(name, decimal_args.len()) {
+                Some(f) => f(&decimal_args),
+                None => Err(format!("function [{}] cannot take {} argument(s)", name, decimal_args.len()))
+            }
+        };
+        match result {
+            Ok(x) => Ok(Rational::from_bigdecimal(&x)),
+            Err(m) => Err(m)
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn enter_calc(calc: &mut Calculator, calc_text: &str) {
+        calc.calc = String::from(calc_text);
+        calc.perform_calculation();
+    }
+
+    // the result of the calc, as it's shown in the previous calculations
+    fn enter_calc_and_format(calc: &mut Calculator, calc_text: &str) -> String {
+        enter_calc(calc, calc_text);
+        Calculator::format_prev_calculation(&calc.prev_calcs.last().unwrap().1)
+    }
+
+    fn enter_calc_and_get_result<'a>(calc: &'a mut Calculator, calc_text: &str) -> &'a CalcResult {
+        enter_calc(calc, calc_text);
+        &calc.prev_calcs.last().unwrap().1
+    }
+
+    // like selecting the result of the nth most recent calc and hitting space
+    fn recall_result(calc: &mut Calculator, nth_most_recent: u8) -> &str {
+        calc.selected_calc = nth_most_recent;
+        calc.selected_equals = true;
+        calc.recall_previous_calc();
+        &calc.calc
+    }
+
+    fn rational(numer: i64, denom: i64) -> Rational {
+        Rational::new(BigInt::from(numer), BigInt::from(denom)).unwrap()
+    }
+
+    #[test]
+    fn tokenize_empty() {
+        assert_eq!(Vec::<String>::new(), Calculator::parse_calc_to_tokens("").unwrap());
+    }
+
+    #[test]
+    fn tokenize_invalid_char() {
+        assert_eq!(Err("unable to read [?] in the calculation".to_string()), Calculator::parse_calc_to_tokens("1 ? 2"));
+    }
+
+    #[test]
+    fn tokenize_only_space() {
+        assert_eq!(Vec::<String>::new(), Calculator::parse_calc_to_tokens("  ").unwrap());
+    }
+
+    #[test]
+    fn tokenize_operator() {
+        assert_eq!(vec!["1", "+", "2"], Calculator::parse_calc_to_tokens("1 + 2").unwrap());
+    }
+
+    #[test]
+    fn tokenize_multichar_value() {
+        assert_eq!(vec!["123"], Calculator::parse_calc_to_tokens("123").unwrap());
+    }
+
+    #[test]
+    fn tokenize_decimal() {
+        assert_eq!(vec!["123.456", "*", "0.789"], Calculator::parse_calc_to_tokens("123.456 * 0.789").unwrap());
+    }
+
+    #[test]
+    fn tokenize_negative_only() {
+        assert_eq!(vec!["-123.456"], Calculator::parse_calc_to_tokens("-123.456").unwrap());
+    }
+
+    #[test]
+    fn tokenize_negative_value() {
+        assert_eq!(vec!["1", "-", "-1"], Calculator::parse_calc_to_tokens("1 - -1").unwrap());
+    }
+
+    #[test]
+    fn tokenize_paren_negative_value() {
+        assert_eq!(vec!["1", "-", "(", "-1", ")"], Calculator::parse_calc_to_tokens("1 - (-1)").unwrap());
+    }
+
+    #[test]
+    fn tokenize_paren_then_subtract() {
+        assert_eq!(vec!["(", "1", ")", "-", "1"], Calculator::parse_calc_to_tokens("(1)-1").unwrap());
+    }
+
+    #[test]
+    fn tokenize_nested_paren() {
+        assert_eq!(vec!["(","(","1","*","2",")","*","3",")"], Calculator::parse_calc_to_tokens("((1*2)*3)").unwrap());
+    }
+
+    #[test]
+    fn tokenize_not_starting_nested_paren() {
+        assert_eq!(vec!["1","+","(","(","2",")",")"], Calculator::parse_calc_to_tokens("1+((2))").unwrap());
+    }
+
+    #[test]
+    fn tokenize_double_plus() {
+        assert_eq!(vec!["1","+","+","2"], Calculator::parse_calc_to_tokens("1++2").unwrap());
+    }
+
+    #[test]
+    fn tokenize_double_times() {
+        assert_eq!(vec!["1","*","*","2"], Calculator::parse_calc_to_tokens("1**2").unwrap());
+    }
+
+    #[test]
+    fn tokenize_floor_divide() {
+        assert_eq!(vec!["1","//","2"], Calculator::parse_calc_to_tokens("1//2").unwrap());
+    }
+
+    #[test]
+    fn tokenize_floor_divide_negative() {
+        assert_eq!(vec!["1","//","-2"], Calculator::parse_calc_to_tokens("1//-2").unwrap());
+    }
+
+    #[test]
+    fn tokenize_remainder() {
+        assert_eq!(vec!["7","mod","3"], Calculator::parse_calc_to_tokens("7 % 3").unwrap());
+    }
+
+    #[test]
+    fn tokenize_percent() {
+        assert_eq!(vec!["200","+","15","%"], Calculator::parse_calc_to_tokens("200 + 15%").unwrap());
+        assert_eq!(vec!["200","-","15","%","-","5"], Calculator::parse_calc_to_tokens("200 - 15% - 5").unwrap());
+        assert_eq!(vec!["15","%","of","80"], Calculator::parse_calc_to_tokens("15% of 80").unwrap());
+    }
+
+    #[test]
+    fn tokenize_remainder_before_name() {
+        assert_eq!(vec!["7","mod","x"], Calculator::parse_calc_to_tokens("7 % x").unwrap());
+        assert_eq!(vec!["7","mod","(","1",")"], Calculator::parse_calc_to_tokens("7%(1)").unwrap());
+        assert_eq!(vec!["7","mod","3"], Calculator::parse_calc_to_tokens("7 mod 3").unwrap());
+    }
+
+    #[test]
+    fn tokenize_remainder_signed_divisor() {
+        assert_eq!(vec!["7","mod","-3"], Calculator::parse_calc_to_tokens("7%-3").unwrap());
+        assert_eq!(vec!["7","mod","-3"], Calculator::parse_calc_to_tokens("7 % -3").unwrap());
+        assert_eq!(vec!["7","mod","+","x"], Calculator::parse_calc_to_tokens("7 %+x").unwrap());
+        assert_eq!(vec!["15","%","-","5"], Calculator::parse_calc_to_tokens("15% - 5").unwrap());
+        assert_eq!(vec!["15","%","-","5"], Calculator::parse_calc_to_tokens("15%- 5").unwrap());
+    }
+
+    #[test]
+    fn tokenize_space_ends_name() {
+        assert_eq!(vec!["x","mod","y"], Calculator::parse_calc_to_tokens("x mod y").unwrap());
+        assert_eq!(vec!["log10"], Calculator::parse_calc_to_tokens("log10").unwrap());
+        assert_eq!(vec!["1000"], Calculator::parse_calc_to_tokens("1 000").unwrap());
+    }
+
+    #[test]
+    fn tokenize_scientific_notation() {
+        assert_eq!(vec!["6.022e23"], Calculator::parse_calc_to_tokens("6.022e23").unwrap());
+        assert_eq!(vec!["1.5E-9","*","2"], Calculator::parse_calc_to_tokens("1.5E-9*2").unwrap());
+        assert_eq!(vec!["-1e+3"], Calculator::parse_calc_to_tokens("-1e+3").unwrap());
+    }
+
+    #[test]
+    fn tokenize_scientific_notation_or_constant_e() {
+        assert_eq!(vec!["1","*","e","-","3"], Calculator::parse_calc_to_tokens("1e - 3").unwrap());
+        assert_eq!(vec!["1","*","e","-","3"], Calculator::parse_calc_to_tokens("1e -3").unwrap());
+        assert_eq!(vec!["2","*","e"], Calculator::parse_calc_to_tokens("2e").unwrap());
+        assert_eq!(vec!["2","*","exp","(","1",")"], Calculator::parse_calc_to_tokens("2exp(1)").unwrap());
+    }
+
+    #[test]
+    fn tokenize_base_literals() {
+        assert_eq!(vec!["0xFF","+","0o755","-","0b1011"], Calculator::parse_calc_to_tokens("0xFF + 0o755 - 0b1011").unwrap());
+        assert_eq!(vec!["-0xdead_beef"], Calculator::parse_calc_to_tokens("-0xdead_beef").unwrap());
+        assert_eq!(vec!["0x1e3"], Calculator::parse_calc_to_tokens("0x1e3").unwrap());
+    }
+
+    #[test]
+    fn tokenize_base_literal_prefix_without_digits() {
+        assert_eq!(vec!["0","*","xyz"], Calculator::parse_calc_to_tokens("0xyz").unwrap());
+        assert_eq!(vec!["0","*","b"], Calculator::parse_calc_to_tokens("0b").unwrap());
+    }
+
+    #[test]
+    fn find_base_literals() {
+        assert_eq!(vec![(0, 4), (5, 13)], CalcJumpToken::find_base_literals("0xFF+0b1010_1"));
+        assert!(CalcJumpToken::find_base_literals("10xFF + x0b1").is_empty());
+    }
+
+    #[test]
+    fn move_cursor_right_token_over_base_literal() {
+        let mut calc = Calculator::new();
+        calc.calc = String::from("0xFF+1");
+        calc.move_cursor_right_token();
+        assert_eq!(3, calc.calc_pos);
+    }
+
+    #[test]
+    fn tokenize_minus_plus() {
+        assert_eq!(vec!["1","-","+","2"], Calculator::parse_calc_to_tokens("1-+2").unwrap());
+    }
+
+    #[test]
+    fn tokenize_multiply_divide() {
+        assert_eq!(vec!["1","*","/","2"], Calculator::parse_calc_to_tokens("1*/2").unwrap());
+    }
+
+    #[test]
+    fn tokenize_triple_minus() {
+        assert_eq!(vec!["1","-","-","-2"], Calculator::parse_calc_to_tokens("1---2").unwrap());
+    }
+
+    #[test]
+    fn tokenize_unary_minus_and_plus() {
+        assert_eq!(vec!["-","(","3","+","4",")"], Calculator::parse_calc_to_tokens("-(3+4)").unwrap());
+        assert_eq!(vec!["2","*","-","(","1","+","1",")"], Calculator::parse_calc_to_tokens("2*-(1+1)").unwrap());
+        assert_eq!(vec!["-","-5"], Calculator::parse_calc_to_tokens("--5").unwrap());
+        assert_eq!(vec!["+","7"], Calculator::parse_calc_to_tokens("+7").unwrap());
+        assert_eq!(vec!["-","x","+","-","pi"], Calculator::parse_calc_to_tokens("-x + -pi").unwrap());
+    }
+
+    #[test]
+    fn tokenize_negative_power_base() {
+        assert_eq!(vec!["-","2","^","2"], Calculator::parse_calc_to_tokens("-2^2").unwrap());
+        assert_eq!(vec!["3","*","-","2.5","^","2"], Calculator::parse_calc_to_tokens("3*-2.5^2").unwrap());
+        assert_eq!(vec!["2","^","-3"], Calculator::parse_calc_to_tokens("2^-3").unwrap());
+    }
+
+    #[test]
+    fn tokenize_power_negative() {
+        assert_eq!(vec!["2","^","-3"], Calculator::parse_calc_to_tokens("2^-3").unwrap());
+    }
+
+    #[test]
+    fn tokenize_bitwise() {
+        assert_eq!(vec!["6","&","3","|","1"], Calculator::parse_calc_to_tokens("6&3|1").unwrap());
+        assert_eq!(vec!["6","xor","3"], Calculator::parse_calc_to_tokens("6 xor 3").unwrap());
+        assert_eq!(vec!["1","<<","4",">>","-2"], Calculator::parse_calc_to_tokens("1<<4>>-2").unwrap());
+        assert_eq!(vec!["~","-5","&","~","(","x",")"], Calculator::parse_calc_to_tokens("~-5 & ~(x)").unwrap());
+        assert_eq!(vec!["7","mod","~","1"], Calculator::parse_calc_to_tokens("7 % ~1").unwrap());
+    }
+
+    #[test]
+    fn tokenize_factorial() {
+        assert_eq!(vec!["5","!","*","2"], Calculator::parse_calc_to_tokens("5! * 2").unwrap());
+        assert_eq!(vec!["(","1","+","2",")","!","!"], Calculator::parse_calc_to_tokens("(1+2)!!").unwrap());
+        assert_eq!(vec!["-","3","!"], Calculator::parse_calc_to_tokens("-3!").unwrap());
+    }
+
+    #[test]
+    fn tokenize_comparisons() {
+        assert_eq!(vec!["x","<=","3"], Calculator::parse_calc_to_tokens("x <= 3").unwrap());
+        assert_eq!(vec!["1","!=","2"], Calculator::parse_calc_to_tokens("1!=2").unwrap());
+        assert_eq!(vec!["5","!","==","120"], Calculator::parse_calc_to_tokens("5! == 120").unwrap());
+        assert_eq!(vec!["a","==","-1"], Calculator::parse_calc_to_tokens("a==-1").unwrap());
+        assert_eq!(vec!["not","x",">","1","and","y"], Calculator::parse_calc_to_tokens("not x > 1 and y").unwrap());
+    }
+
+    #[test]
+    fn tokenize_function() {
+        assert_eq!(vec!["sqrt","(","2",")"], Calculator::parse_calc_to_tokens("sqrt(2)").unwrap());
+    }
+
+    #[test]
+    fn tokenize_function_with_digits() {
+        assert_eq!(vec!["log10","(","100",")","*","2"], Calculator::parse_calc_to_tokens("log10(100)*2").unwrap());
+    }
+
+    #[test]
+    fn tokenize_function_negative_argument() {
+        assert_eq!(vec!["2","*","abs","(","-3.5",")"], Calculator::parse_calc_to_tokens("2 * abs(-3.5)").unwrap());
+    }
+
+    #[test]
+    fn tokenize_function_arguments() {
+        assert_eq!(vec!["max","(","1",",","-2",",","3",")"], Calculator::parse_calc_to_tokens("max(1, -2, 3)").unwrap());
+    }
+
+    #[test]
+    fn tokenize_thousands_separator_within_function_arguments() {
+        assert_eq!(vec!["max","(","(","1000",")",",","2",")"], Calculator::parse_calc_to_tokens("max((1,000), 2)").unwrap());
+    }
+
+    #[test]
+    fn tokenize_thousands_separators() {
+        assert_eq!(vec!["1000000","+","1"], Calculator::parse_calc_to_tokens("$1,000,000 + 1").unwrap());
+    }
+
+    #[test]
+    fn tokenize_history_references() {
+        assert_eq!(vec!["ans","*","#12"], Calculator::parse_calc_to_tokens("ans * #12").unwrap());
+    }
+
+    #[test]
+    fn tokenize_implicit_multiplication() {
+        assert_eq!(vec!["2","*","(","3","+","4",")"], Calculator::parse_calc_to_tokens("2(3+4)").unwrap());
+        assert_eq!(vec!["(","1",")","*","(","2",")"], Calculator::parse_calc_to_tokens("(1)(2)").unwrap());
+        assert_eq!(vec!["3","*","pi"], Calculator::parse_calc_to_tokens("3pi").unwrap());
+        assert_eq!(vec!["(","1",")","*","2"], Calculator::parse_calc_to_tokens("(1)2").unwrap());
+    }
+
+    #[test]
+    fn tokenize_function_call_is_not_implicit_multiplication() {
+        assert_eq!(vec!["x","(","2",")"], Calculator::parse_calc_to_tokens("x(2)").unwrap());
+    }
+
+    #[test]
+    fn tokenize_repeating_decimal() {
+        assert_eq!(vec!["0.(3)","*","3"], Calculator::parse_calc_to_tokens("0.(3)*3").unwrap());
+        assert_eq!(vec!["-1.2(34)"], Calculator::parse_calc_to_tokens("-1.2(34)").unwrap());
+        assert_eq!(vec!["1.5","*","(","2","+","1",")"], Calculator::parse_calc_to_tokens("1.5(2+1)").unwrap());
+        assert_eq!(vec!["15","*","(","3",")"], Calculator::parse_calc_to_tokens("15(3)").unwrap());
+        assert_eq!(vec!["1.5(2)"], Calculator::parse_calc_to_tokens("1.5(2)").unwrap());
+        assert_eq!(vec!["1.5","*","(","2",")"], Calculator::parse_calc_to_tokens("1.5 (2)").unwrap());
+    }
+
+    #[test]
+    fn tree_add_minimal() {
+        let mut tree = Tree::<&str>::new();
+
+        let index_loc = tree.add_node(TreeNode::new("a"));
+
+        assert_eq!(0, index_loc);
+    }
+
+    #[test]
+    fn tree_add_a_few() {
+        let mut tree = Tree::<&str>::new();
+
+        let a = tree.add_node(TreeNode::new("a"));
+        let b = tree.add_node(TreeNode::new("b"));
+        let c = tree.add_node_with_children(TreeNode::new("c"), Some(a), Some(b));
+
+        assert_eq!(2, c);
+    }
+
+    #[test]
+    fn tree_postorder_empty() {
+        let tree = Tree::<&str>::new();
+
+        let mut output = String::from("");
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push_str(node.value);
+        }
+        assert_eq!("", output);
+    }
+
+    #[test]
+    fn tree_postorder_index_minimal() {
+        let mut tree = Tree::<&str>::new();
+
+        let a = tree.add_node(TreeNode::new("a"));
+        tree.set_root(Some(a));
+
+        let mut postorder = PostOrderIter::new(&tree);
+        let index_loc = postorder.next().expect("Node does not exist at given index");
+
+        assert_eq!(0, index_loc);
+    }
+
+    #[test]
+    fn tree_postorder_minimal() {
+        let mut tree = Tree::<&str>::new();
+
+        let a = tree.add_node(TreeNode::new("a"));
+        tree.set_root(Some(a));
+
+        let mut output = String::from("");
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push_str(node.value);
+        }
+        assert_eq!("a", output);
+    }
+
+    // example tree from https://en.wikipedia.org/wiki/Tree_traversal#Post-order_(LRN)
+    #[test]
+    fn tree_postorder_complex() {
+        let mut tree = Tree::<&str>::new();
+
+        let a = tree.add_node(TreeNode::new("a"));
+        let c = tree.add_node(TreeNode::new("c"));
+        let e = tree.add_node(TreeNode::new("e"));
+        let d = tree.add_node_with_children(TreeNode::new("d"), Some(c), Some(e));
+        let b = tree.add_node_with_children(TreeNode::new("b"), Some(a), Some(d));
+        let h = tree.add_node(TreeNode::new("h"));
+        let i = tree.add_node_with_children(TreeNode::new("i"), Some(h), None);
+        let g = tree.add_node_with_children(TreeNode::new("g"), None, Some(i));
+        let f = tree.add_node_with_children(TreeNode::new("f"), Some(b), Some(g));
+        tree.set_root(Some(f));
+
+        let mut output = String::from("");
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push_str(node.value);
+        }
+        assert_eq!("acedbhigf", output);
+    }
+
+    #[test]
+    fn get_char_token_digit() {
+        for c in "0123456789".chars() {
+            assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_char(c).unwrap());
+        }
+    }
+
+    #[test]
+    fn get_char_token_operator() {
+        for c in "+-*/^%<>=".chars() {
+            assert_eq!(CalcParseToken::Operator, CalcParseToken::get_token_matching_char(c).unwrap());
+        }
+    }
+
+    #[test]
+    fn get_char_token_open_paren() {
+        assert_eq!(CalcParseToken::OpenParen, CalcParseToken::get_token_matching_char('(').unwrap());
+    }
+
+    #[test]
+    fn get_char_token_close_paren() {
+        assert_eq!(CalcParseToken::CloseParen, CalcParseToken::get_token_matching_char(')').unwrap());
+    }
+
+    #[test]
+    fn get_char_token_identifier() {
+        for c in "abcXYZ_".chars() {
+            assert_eq!(CalcParseToken::Identifier, CalcParseToken::get_token_matching_char(c).unwrap());
+        }
+    }
+
+    #[test]
+    fn get_char_token_invalid() {
+        for c in "$,".chars() {
+            assert_eq!(None, CalcParseToken::get_token_matching_char(c));
+        }
+    }
+
+    #[test]
+    fn get_char_token_postfix() {
+        assert_eq!(Some(CalcParseToken::Postfix), CalcParseToken::get_token_matching_char('!'));
+    }
+
+    #[test]
+    fn get_str_token_digit() {
+        for c in "0123456789".chars() {
+            assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str(&String::from(c)).unwrap());
+        }
+    }
+
+    #[test]
+    fn get_str_token_operator() {
+        for c in "+-*/^".chars() {
+            assert_eq!(CalcParseToken::Operator, CalcParseToken::get_token_matching_str(&String::from(c)).unwrap());
+        }
+        assert_eq!(Some(CalcParseToken::Operator), CalcParseToken::get_token_matching_str("mod"));
+        assert_eq!(Some(CalcParseToken::Operator), CalcParseToken::get_token_matching_str("of"));
+    }
+
+    #[test]
+    fn get_str_token_percent() {
+        assert_eq!(Some(CalcParseToken::Postfix), CalcParseToken::get_token_matching_str("%"));
+    }
+
+    #[test]
+    fn get_str_token_scientific_notation() {
+        assert_eq!(Some(CalcParseToken::Value), CalcParseToken::get_token_matching_str("6.022e23"));
+        assert_eq!(Some(CalcParseToken::Value), CalcParseToken::get_token_matching_str("-1.5E+3"));
+        assert_eq!(Some(CalcParseToken::Value), CalcParseToken::get_token_matching_str(".5e-3"));
+        assert_eq!(None, CalcParseToken::get_token_matching_str("1e"));
+        assert_eq!(None, CalcParseToken::get_token_matching_str("1e-"));
+        assert_eq!(None, CalcParseToken::get_token_matching_str("1e3.5"));
+        assert_eq!(None, CalcParseToken::get_token_matching_str("1e3e3"));
+    }
+
+    #[test]
+    fn get_str_token_base_literal() {
+        assert_eq!(Some(CalcParseToken::Value), CalcParseToken::get_token_matching_str("0xFF"));
+        assert_eq!(Some(CalcParseToken::Value), CalcParseToken::get_token_matching_str("-0b1010_1010"));
+        assert_eq!(None, CalcParseToken::get_token_matching_str("0x"));
+        assert_eq!(None, CalcParseToken::get_token_matching_str("0xFF_"));
+        assert_eq!(None, CalcParseToken::get_token_matching_str("0b102"));
+        assert_eq!(None, CalcParseToken::get_token_matching_str("0o8"));
+    }
+
+    #[test]
+    fn get_str_token_floor_divide() {
+        assert_eq!(CalcParseToken::Operator, CalcParseToken::get_token_matching_str("//").unwrap());
+    }
+
+    #[test]
+    fn get_str_token_triple_divide() {
+        assert_eq!(None, CalcParseToken::get_token_matching_str("///"));
+    }
+
+    #[test]
+    fn get_str_token_open_paren() {
+        assert_eq!(CalcParseToken::OpenParen, CalcParseToken::get_token_matching_str("(").unwrap());
+    }
+
+    #[test]
+    fn get_str_token_close_paren() {
+        assert_eq!(CalcParseToken::CloseParen, CalcParseToken::get_token_matching_str(")").unwrap());
+    }
+
+    #[test]
+    fn get_str_token_integer() {
+        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str("123").unwrap());
+    }
+
+    #[test]
+    fn get_str_token_negative_integer() {
+        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str("-456").unwrap());
+    }
+
+    #[test]
+    fn get_str_token_decimal() {
+        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str("3.14").unwrap());
+    }
+
+    #[test]
+    fn get_str_token_negative_decimal() {
+        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str("-3.14").unwrap());
+    }
+
+    #[test]
+    fn get_str_token_zero_decimal() {
+        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str("0.42").unwrap());
+    }
+
+    #[test]
+    fn get_str_token_negative_zero_decimal() {
+        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str("-0.42").unwrap());
+    }
+
+    #[test]
+    fn get_str_token_just_decimal() {
+        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str(".25").unwrap());
+    }
+
+    #[test]
+    fn get_str_token_contains_open_paren() {
+        assert_eq!(None, CalcParseToken::get_token_matching_str("(10"));
+    }
+
+    #[test]
+    fn get_str_token_contains_close_paren() {
+        assert_eq!(None, CalcParseToken::get_token_matching_str("10)"));
+    }
+
+    #[test]
+    fn get_str_token_negative_just_decimal() {
+        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str("-.25").unwrap());
+    }
+
+    #[test]
+    fn get_str_token_two_decimals() {
+        assert_eq!(None, CalcParseToken::get_token_matching_str("5.2.5"));
+    }
+
+    #[test]
+    fn get_str_token_negative_two_decimals() {
+        assert_eq!(None, CalcParseToken::get_token_matching_str("-.2.5"));
+    }
+
+    #[test]
+    fn get_str_token_history_reference() {
+        assert_eq!(Some(CalcParseToken::Identifier), CalcParseToken::get_token_matching_str("#3"));
+        assert_eq!(None, CalcParseToken::get_token_matching_str("#"));
+        assert_eq!(None, CalcParseToken::get_token_matching_str("#x"));
+    }
+
+    #[test]
+    fn get_str_token_identifier() {
+        assert_eq!(CalcParseToken::Identifier, CalcParseToken::get_token_matching_str("sqrt").unwrap());
+    }
+
+    #[test]
+    fn get_str_token_identifier_with_digits() {
+        assert_eq!(CalcParseToken::Identifier, CalcParseToken::get_token_matching_str("log10").unwrap());
+    }
+
+    #[test]
+    fn get_str_token_identifier_starting_with_digit() {
+        assert_eq!(None, CalcParseToken::get_token_matching_str("10log"));
+    }
+
+    #[test]
+    fn get_str_token_two_negatives() {
+        assert_eq!(None, CalcParseToken::get_token_matching_str("-2-5"));
+    }
+
+    #[test]
+    fn build_tree_add() {
+        let tree = Calculator::build_calc_eval_tree("123 + 456").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["123","456","+"], output);
+    }
+
+    #[test]
+    fn build_tree_multiply() {
+        let tree = Calculator::build_calc_eval_tree("123 * 456").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["123","456","*"], output);
+    }
+
+    #[test]
+    fn build_tree_add_three() {
+        let tree = Calculator::build_calc_eval_tree("123 + 456 + 789").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["123","456","+","789","+"], output);
+    }
+
+    #[test]
+    fn build_tree_add_then_multiply() {
+        let tree = Calculator::build_calc_eval_tree("123 + 456 * 789").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["123","456","789","*","+"], output);
+    }
+
+    #[test]
+    fn build_tree_add_multiply_add() {
+        let tree = Calculator::build_calc_eval_tree("1 + 2 * 3 + 4").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["1","2","3","*","+","4","+"], output);
+    }
+
+    #[test]
+    fn build_tree_multiply_add_multiply() {
+        let tree = Calculator::build_calc_eval_tree("1 * 2 + 3 * 4").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["1","2","*","3","4","*","+"], output);
+    }
+
+    #[test]
+    fn build_tree_multiply_multiply_multiply() {
+        let tree = Calculator::build_calc_eval_tree("1 * 2 * 3 * 4").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        // "*" and "/" are evaluated left to right, so a chain of them nests
+        //   to the left -- it used to nest to the right, which didn't matter
+        //   for "*" but made "8/4/2" 4 rather than 1
+        assert_eq!(vec!["1","2","*","3","*","4","*"], output);
+    }
+
+    #[test]
+    fn build_tree_divide_divide() {
+        let tree = Calculator::build_calc_eval_tree("8 / 4 / 2").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["8","4","/","2","/"], output);
+    }
+
+    #[test]
+    fn build_tree_remainder_multiply_floor_divide() {
+        let tree = Calculator::build_calc_eval_tree("7 % 3 * 2 // 4 + 1").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["7","3","mod","2","*","4","//","1","+"], output);
+    }
+
+    #[test]
+    fn build_tree_power_power() {
+        let tree = Calculator::build_calc_eval_tree("2^3^2").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["2","3","2","^","^"], output);
+    }
+
+    #[test]
+    fn build_tree_multiply_then_power() {
+        let tree = Calculator::build_calc_eval_tree("2*3^2").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["2","3","2","^","*"], output);
+    }
+
+    #[test]
+    fn build_tree_power_then_multiply() {
+        let tree = Calculator::build_calc_eval_tree("2^3*4").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["2","3","^","4","*"], output);
+    }
+
+    #[test]
+    fn build_tree_paren_add() {
+        let tree = Calculator::build_calc_eval_tree("(1 + 2").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["1","2","+","("], output);
+    }
+
+    #[test]
+    fn build_tree_two_parens_then_multiply() {
+        let tree = Calculator::build_calc_eval_tree("(1+2) + (3+4)*5").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["1","2","+",")","3","4","+",")","5","*","+"], output);
+    }
+
+    #[test]
+    fn build_tree_nested_parens() {
+        let tree = Calculator::build_calc_eval_tree("((1+2)*(3+4))/5").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["1","2","+",")","3","4","+",")","*",")","5","/"], output);
+    }
+
+    #[test]
+    fn build_tree_function() {
+        let tree = Calculator::build_calc_eval_tree("2*sqrt(1+3)^2").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["2","1","3","+",")","sqrt","2","^","*"], output);
+    }
+
+    #[test]
+    fn build_tree_nested_functions() {
+        let tree = Calculator::build_calc_eval_tree("abs(floor(-2.5))+1").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["-2.5",")","floor",")","abs","1","+"], output);
+    }
+
+    #[test]
+    fn build_tree_function_arguments() {
+        let tree = Calculator::build_calc_eval_tree("max(1+2, 3, 4)").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["1","2","+","3",",","4",",",")","max"], output);
+    }
+
+    #[test]
+    fn build_tree_function_then_open_paren() {
+        let tree = Calculator::build_calc_eval_tree("sqrt(4)(2)").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["4",")","sqrt","2",")","*"], output);
+    }
+
+    #[test]
+    fn build_tree_commas_spaces_and_dollar_signs() {
+        let tree = Calculator::build_calc_eval_tree("$1,000 * .25 + 22").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["1000",".25","*","22","+"], output);
+    }
+
+    #[test]
+    fn build_tree_bitwise_precedence() {
+        let tree = Calculator::build_calc_eval_tree("1 | 6 & 3 << 1 xor 2 + 2").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["1","6","3","1","<<","&","2","2","+","xor","|"], output);
+    }
+
+    #[test]
+    fn build_tree_unary_not() {
+        let tree = Calculator::build_calc_eval_tree("~2^2*~(1+2)").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["2","2","^","~","1","2","+",")","~","*"], output);
+        assert!(Calculator::build_calc_eval_tree("2~3").is_err());
+        assert!(Calculator::build_calc_eval_tree("~*3").is_err());
+        assert!(Calculator::build_calc_eval_tree("(~)").is_err());
+    }
+
+    #[test]
+    fn build_tree_unary_minus() {
+        let tree = Calculator::build_calc_eval_tree("2*-x^2-+1").unwrap();
+        let mut output = Vec::<&str>::new();
+        for index in PostOrderIter::new(&tree) {
+            let node = tree.node_at(index).expect("Node does not exist at given index");
+            output.push(&node.value.string_value);
+        }
+        assert_eq!(vec!["2","x","2","^","-","*","1","+","-"], output);
+        assert!(Calculator::build_calc_eval_tree("(-)").is_err());
+    }
+
+    #[test]
+    fn evaluate_simple_add() {
+        let result = Calculator::new().evaluate_calc("1+1").unwrap();
+        assert_eq!("2", result);
+    }
+
+    #[test]
+    fn evaluate_simple_subtract() {
+        let result = Calculator::new().evaluate_calc("1-2").unwrap();
+        assert_eq!("-1", result);
+    }
+
+    #[test]
+    fn evaluate_simple_multiply() {
+        let result = Calculator::new().evaluate_calc("2*2").unwrap();
+        assert_eq!("4", result);
+    }
+
+    #[test]
+    fn evaluate_simple_divide() {
+        let result = Calculator::new().evaluate_calc("6/2").unwrap();
+        assert_eq!("3", result);
+    }
+
+    #[test]
+    fn evaluate_nested_parens() {
+        let result = Calculator::new().evaluate_calc("((1+2)*(3+4))/7").unwrap();
+        assert_eq!("3", result);
+    }
+
+    #[test]
+    fn evaluate_divide_divide() {
+        let result = Calculator::new().evaluate_calc("8/4/2").unwrap();
+        assert_eq!("1", result);
+    }
+
+    #[test]
+    fn evaluate_divide_by_zero() {
+        assert!(Calculator::new().evaluate_calc("1/(2-2)").is_err());
+    }
+
+    #[test]
+    fn evaluate_remainder() {
+        let result = Calculator::new().evaluate_calc("7%3").unwrap();
+        assert_eq!("1", result);
+    }
+
+    #[test]
+    fn evaluate_remainder_negative_dividend() {
+        let result = Calculator::new().evaluate_calc("-7%3").unwrap();
+        assert_eq!("2", result);
+    }
+
+    #[test]
+    fn evaluate_remainder_negative_divisor() {
+        let result = Calculator::new().evaluate_calc("7%-3").unwrap();
+        assert_eq!("-2", result);
+    }
+
+    #[test]
+    fn evaluate_percent() {
+        let calc = Calculator::new();
+        assert_eq!("0.5", calc.evaluate_calc("50%").unwrap());
+        assert_eq!("230", calc.evaluate_calc("200 + 15%").unwrap());
+        assert_eq!("170", calc.evaluate_calc("200 - 15%").unwrap());
+        assert_eq!("20", calc.evaluate_calc("80 * 25%").unwrap());
+        assert_eq!("2", calc.evaluate_calc("1 / 50%").unwrap());
+        assert_eq!("12", calc.evaluate_calc("15% of 80").unwrap());
+    }
+
+    #[test]
+    fn evaluate_percent_chained() {
+        let calc = Calculator::new();
+        assert_eq!("242", calc.evaluate_calc("200 + 10% + 10%").unwrap());
+        assert_eq!("165", calc.evaluate_calc("200 - 15% - 5").unwrap());
+        assert_eq!("210", calc.evaluate_calc("200 + (10% of 100)").unwrap());
+    }
+
+    #[test]
+    fn evaluate_percent_in_parens() {
+        let calc = Calculator::new();
+        assert_eq!("230", calc.evaluate_calc("200 + (15%)").unwrap());
+        assert_eq!("170", calc.evaluate_calc("200 - ((15%))").unwrap());
+        assert_eq!("12", calc.evaluate_calc("(15%) of 80").unwrap());
+    }
+
+    #[test]
+    fn evaluate_percent_of_paren_group() {
+        assert_eq!("120", Calculator::new().evaluate_calc("100 + (10 + 10)%").unwrap());
+    }
+
+    #[test]
+    fn evaluate_of_without_percent() {
+        assert!(Calculator::new().evaluate_calc("2 of 3").is_err());
+    }
+
+    #[test]
+    fn evaluate_percent_first() {
+        assert!(Calculator::new().evaluate_calc("% 5").is_err());
+    }
+
+    #[test]
+    fn evaluate_scientific_notation() {
+        let calc = Calculator::new();
+        assert_eq!("602200000000000000000000", calc.evaluate_calc("6.022e23").unwrap());
+        assert_eq!("0.0000000015", calc.evaluate_calc("1.5E-9").unwrap());
+        assert_eq!("0.001", calc.evaluate_calc("1e-3").unwrap());
+        assert_eq!("-0.28171817154095476463971252864733750224275290630004", calc.evaluate_calc("1e - 3").unwrap());
+    }
+
+    #[test]
+    fn evaluate_scientific_notation_exponent_too_large() {
+        assert!(Calculator::new().evaluate_calc("1e999999999").is_err());
+    }
+
+    #[test]
+    fn perform_calculation_scientific_notation_exponent_too_large() {
+        let mut calc = Calculator::new();
+        let result = enter_calc_and_get_result(&mut calc, "1e99999999");
+        assert_eq!(CalcResult::Error("the exponent of value [1e99999999] is too large".to_string()), *result);
+        let result = enter_calc_and_get_result(&mut calc, "2 * 1e-99999999");
+        assert_eq!(CalcResult::Error("the exponent of value [1e-99999999] is too large".to_string()), *result);
+    }
+
+    #[test]
+    fn evaluate_base_literals() {
+        let calc = Calculator::new();
+        assert_eq!("255", calc.evaluate_calc("0xFF").unwrap());
+        assert_eq!("493", calc.evaluate_calc("0o755").unwrap());
+        assert_eq!("11", calc.evaluate_calc("0b1011").unwrap());
+        assert_eq!("-3735928559", calc.evaluate_calc("-0xDEAD_BEEF").unwrap());
+        assert_eq!("340282366920938463463374607431768211455", calc.evaluate_calc("0xFFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF_FFFF").unwrap());
+        assert_eq!("16", calc.evaluate_calc("0x10 * 0b1").unwrap());
+    }
+
+    #[test]
+    fn evaluate_remainder_decimal() {
+        let result = Calculator::new().evaluate_calc("7.5%2").unwrap();
+        assert_eq!("1.5", result);
+    }
+
+    #[test]
+    fn evaluate_remainder_by_zero() {
+        assert!(Calculator::new().evaluate_calc("7%0").is_err());
+    }
+
+    #[test]
+    fn evaluate_floor_divide() {
+        let result = Calculator::new().evaluate_calc("7//2").unwrap();
+        assert_eq!("3", result);
+    }
+
+    #[test]
+    fn evaluate_floor_divide_negative() {
+        let result = Calculator::new().evaluate_calc("-7//2").unwrap();
+        assert_eq!("-4", result);
+    }
+
+    #[test]
+    fn evaluate_floor_divide_decimal() {
+        let result = Calculator::new().evaluate_calc("7.5//.5").unwrap();
+        assert_eq!("15", result);
+    }
+
+    #[test]
+    fn evaluate_floor_divide_by_zero() {
+        assert!(Calculator::new().evaluate_calc("7//0").is_err());
+    }
+
+    #[test]
+    fn evaluate_power() {
+        let result = Calculator::new().evaluate_calc("2^10").unwrap();
+        assert_eq!("1024", result);
+    }
+
+    #[test]
+    fn evaluate_power_power() {
+        let result = Calculator::new().evaluate_calc("2^3^2").unwrap();
+        assert_eq!("512", result);
+    }
+
+    #[test]
+    fn evaluate_power_precedence() {
+        let result = Calculator::new().evaluate_calc("1+2*3^2").unwrap();
+        assert_eq!("19", result);
+    }
+
+    #[test]
+    fn evaluate_negative_power() {
+        let result = Calculator::new().evaluate_calc("2^-2").unwrap();
+        assert_eq!("0.25", result);
+    }
+
+    #[test]
+    fn evaluate_fractional_power() {
+        let result = Calculator::new().evaluate_calc("4^0.5").unwrap();
+        assert_eq!("2", result);
+    }
+
+    #[test]
+    fn evaluate_fractional_power_irrational() {
+        let result = Calculator::new().evaluate_calc("2^.5").unwrap();
+        assert_eq!("1.4142135623730950488016887242096980785696718753769", result);
+    }
+
+    #[test]
+    fn evaluate_negative_base_fractional_power() {
+        assert!(Calculator::new().evaluate_calc("(-8)^0.5").is_err());
+    }
+
+    #[test]
+    fn evaluate_unary_minus_and_plus() {
+        let calc = Calculator::new();
+        assert_eq!("-7", calc.evaluate_calc("-(3+4)").unwrap());
+        assert_eq!("-4", calc.evaluate_calc("2*-(1+1)").unwrap());
+        assert_eq!("5", calc.evaluate_calc("--5").unwrap());
+        assert_eq!("7", calc.evaluate_calc("+7").unwrap());
+        assert_eq!("-4", calc.evaluate_calc("-2^2").unwrap());
+        assert_eq!("4", calc.evaluate_calc("(-2)^2").unwrap());
+        assert_eq!("0.125", calc.evaluate_calc("2^-3").unwrap());
+        assert_eq!("-1", calc.evaluate_calc("1---2").unwrap());
+        assert!(calc.evaluate_calc("-").is_err());
+        assert!(calc.evaluate_calc("3*-").is_err());
+        assert_eq!(Ok(rational(-7, 3)), calc.evaluate_calc_exact("-(1/3 + 2)"));
+    }
+
+    #[test]
+    fn evaluate_bitwise() {
+        let calc = Calculator::new();
+        assert_eq!("2", calc.evaluate_calc("6 & 3").unwrap());
+        assert_eq!("7", calc.evaluate_calc("6 | 3").unwrap());
+        assert_eq!("5", calc.evaluate_calc("6 xor 3").unwrap());
+        assert_eq!("3", calc.evaluate_calc("1 | 2 & 3").unwrap());
+        assert_eq!("255", calc.evaluate_calc("0xF0 | 0x0F").unwrap());
+        assert_eq!("-8", calc.evaluate_calc("-5 & -4").unwrap());
+    }
+
+    #[test]
+    fn evaluate_bitwise_not() {
+        let calc = Calculator::new();
+        assert_eq!("-6", calc.evaluate_calc("~5").unwrap());
+        assert_eq!("4", calc.evaluate_calc("~-5").unwrap());
+        assert_eq!("-5", calc.evaluate_calc("~2^2").unwrap());
+        assert_eq!("6", calc.evaluate_calc("~~3*2").unwrap());
+    }
+
+    #[test]
+    fn evaluate_shifts() {
+        let calc = Calculator::new();
+        assert_eq!("16", calc.evaluate_calc("1 << 4").unwrap());
+        assert_eq!("32", calc.evaluate_calc("1 << 2 + 3").unwrap());
+        assert_eq!("2", calc.evaluate_calc("5 >> 1").unwrap());
+        assert_eq!("-3", calc.evaluate_calc("-5 >> 1").unwrap());
+        assert!(calc.evaluate_calc("4 << -2").is_err());
+        assert!(calc.evaluate_calc("1 << 1000000").is_err());
+    }
+
+    #[test]
+    fn evaluate_bitwise_needs_integers() {
+        let calc = Calculator::new();
+        assert!(calc.evaluate_calc("1.5 & 1").is_err());
+        assert!(calc.evaluate_calc("~0.5").is_err());
+        assert!(calc.evaluate_calc("1 << 0.5").is_err());
+        assert_eq!("3", calc.evaluate_calc("1.0 | 2").unwrap());
+    }
+
+    #[test]
+    fn evaluate_sqrt() {
+        let result = Calculator::new().evaluate_calc("sqrt(16)").unwrap();
+        assert_eq!("4", result);
+    }
+
+    #[test]
+    fn evaluate_sqrt_irrational() {
+        let result = Calculator::new().evaluate_calc("sqrt(2)").unwrap();
+        assert_eq!("1.4142135623730950488016887242096980785696718753769", result);
+    }
+
+    #[test]
+    fn evaluate_sqrt_negative() {
+        assert!(Calculator::new().evaluate_calc("sqrt(-4)").is_err());
+    }
+
+    #[test]
+    fn evaluate_abs() {
+        let result = Calculator::new().evaluate_calc("abs(-3.5)").unwrap();
+        assert_eq!("3.5", result);
+    }
+
+    #[test]
+    fn evaluate_floor_ceil_round_trunc() {
+        assert_eq!("-3", Calculator::new().evaluate_calc("floor(-2.5)").unwrap());
+        assert_eq!("-2", Calculator::new().evaluate_calc("ceil(-2.5)").unwrap());
+        assert_eq!("-3", Calculator::new().evaluate_calc("round(-2.5)").unwrap());
+        assert_eq!("3", Calculator::new().evaluate_calc("round(2.5)").unwrap());
+        assert_eq!("2", Calculator::new().evaluate_calc("round(2.49)").unwrap());
+        assert_eq!("-2", Calculator::new().evaluate_calc("trunc(-2.7)").unwrap());
+    }
+
+    #[test]
+    fn evaluate_ln() {
+        let result = Calculator::new().evaluate_calc("ln(2)").unwrap();
+        assert_eq!("0.69314718055994530941723212145817656807550013436026", result);
+    }
+
+    #[test]
+    fn evaluate_ln_large() {
+        let result = Calculator::new().evaluate_calc("ln(10)").unwrap();
+        assert_eq!("2.3025850929940456840179914546843642076011014886288", result);
+    }
+
+    #[test]
+    fn evaluate_ln_zero() {
+        assert!(Calculator::new().evaluate_calc("ln(0)").is_err());
+    }
+
+    #[test]
+    fn evaluate_exp() {
+        let result = Calculator::new().evaluate_calc("exp(1)").unwrap();
+        assert_eq!("2.7182818284590452353602874713526624977572470937", result);
+    }
+
+    #[test]
+    fn evaluate_log10() {
+        let result = Calculator::new().evaluate_calc("log10(1000)").unwrap();
+        assert_eq!("3", result);
+    }
+
+    #[test]
+    fn evaluate_log2() {
+        let result = Calculator::new().evaluate_calc("log2(1024)").unwrap();
+        assert_eq!("10", result);
+    }
+
+    #[test]
+    fn evaluate_sin() {
+        let result = Calculator::new().evaluate_calc("sin(1)").unwrap();
+        assert_eq!("0.84147098480789650665250232163029899962256306079837", result);
+    }
+
+    #[test]
+    fn evaluate_sin_large_argument() {
+        let result = Calculator::new().evaluate_calc("sin(1000)").unwrap();
+        assert_eq!("0.82687954053200256025588742910921814121272496784779", result);
+    }
+
+    #[test]
+    fn evaluate_cos_zero() {
+        let result = Calculator::new().evaluate_calc("cos(0)").unwrap();
+        assert_eq!("1", result);
+    }
+
+    #[test]
+    fn evaluate_atan() {
+        let result = Calculator::new().evaluate_calc("atan(1)").unwrap();
+        assert_eq!("0.78539816339744830961566084581987572104929234984378", result);
+    }
+
+    #[test]
+    fn evaluate_asin() {
+        let result = Calculator::new().evaluate_calc("asin(1)").unwrap();
+        assert_eq!("1.5707963267948966192313216916397514420985846996876", result);
+    }
+
+    #[test]
+    fn evaluate_asin_out_of_range() {
+        assert!(Calculator::new().evaluate_calc("asin(2)").is_err());
+    }
+
+    #[test]
+    fn evaluate_tanh() {
+        let result = Calculator::new().evaluate_calc("tanh(0)").unwrap();
+        assert_eq!("0", result);
+    }
 
-                    if cursor.is_none() || !tree.has_root() {
-                        return Err("the first token cannot be a closed paren".to_string());
-                    }
-                    let cursor_loc = cursor.unwrap();
+    #[test]
+    fn evaluate_trig_in_degrees_and_gradians() {
+        let mut calc = Calculator::new();
+        calc.angle_mode = AngleUnit::Degrees;
+        assert_eq!("0.5", calc.evaluate_calc("sin(30)").unwrap());
+        assert_eq!("0.5", calc.evaluate_calc("sin(390)").unwrap());
+        assert_eq!("0", calc.evaluate_calc("cos(90)").unwrap());
+        assert_eq!("-1", calc.evaluate_calc("cos(-180)").unwrap());
+        assert_eq!("1", calc.evaluate_calc("tan(45)").unwrap());
+        assert!(calc.evaluate_calc("tan(270)").is_err());
+        assert_eq!("90", calc.evaluate_calc("asin(1)").unwrap());
+        assert_eq!("60", calc.evaluate_calc("acos(0.5)").unwrap());
+        assert_eq!("45", calc.evaluate_calc("atan(1)").unwrap());
+        assert_eq!("135", calc.evaluate_calc("atan2(1, -1)").unwrap());
+        calc.angle_mode = AngleUnit::Gradians;
+        assert_eq!("100", calc.evaluate_calc("asin(1)").unwrap());
+        assert_eq!("1", calc.evaluate_calc("sin(100)").unwrap());
+    }
 
-                    // this is the "go up then traverse down" approach
-                    // let mut open_paren_loc = cursor_loc;
-                    // while !tree.matches_root(open_paren_loc) {
-                    //     let open_paren_loc_parent_loc = tree.get_node_parent(open_paren_loc);
-                    //     if open_paren_loc_parent_loc.is_none() {
-                    //         return Err("a node that isn't the root has no parent");
-                    //     }
-                    //     open_paren_loc = open_paren_loc_parent_loc.unwrap();
-                    //     let open_paren_node = match tree.node_at(open_paren_loc) {
-                    //         Some(n) => n,
-                    //         None => { return Err("no node exists at a node's parent's location"); }
-                    //     };
-                    //     match open_paren_node.value.token_type {
-                    //         // if the parent is an "(" then stop here
-                    //         CalcParseToken::OpenParen => {
-                    //             break;
-                    //         },
-                    //         _ => ()
-                    //     }
-                    // }
+    #[test]
+    fn evaluate_angle_units() {
+        let mut calc = Calculator::new();
+        assert_eq!("0.5", calc.evaluate_calc("sin(30deg)").unwrap());
+        assert_eq!("0.5", calc.evaluate_calc("sin(30°)").unwrap());
+        assert_eq!("1", calc.evaluate_calc("near(180°, pi)").unwrap());
+        assert_eq!("1", calc.evaluate_calc("near(radians(180), pi)").unwrap());
+        assert_eq!("180", calc.evaluate_calc("degrees(pi)").unwrap());
+        assert_eq!("1.2", calc.evaluate_calc("1.2rad").unwrap());
+        calc.angle_mode = AngleUnit::Degrees;
+        assert!(calc.evaluate_calc("1rad").unwrap().starts_with("57.2957795130823208767981548141"));
+        assert_eq!("180.0", calc.evaluate_calc("200grad").unwrap());
+        assert_eq!("0.5", calc.evaluate_calc("sin(pi/6 rad)").unwrap());
+        assert_eq!(Ok(rational(45, 1)), calc.evaluate_calc_exact("45°"));
+        calc.angle_mode = AngleUnit::Radians;
+        assert!(calc.evaluate_calc_exact("45°").is_err());
+        assert!(Calculator::parse_assignment("deg = 1").is_err());
+    }
 
-                    // let open_paren_node = match tree.node_at(open_paren_loc) {
-                    //     Some(n) => n,
-                    //     None => { return Err("no node exists at found nearest open paren"); }
-                    // };
-                    // match open_paren_node.value.token_type {
-                    //     CalcParseToken::OpenParen => {
-                    //         // do traversal starting at open_paren_loc
-                    //     },
-                    //     _ => {
-                    //         // handle the case where we hit the root before finding an open paren
-                    //         return Err("no corresponding open paren token found for the new close paren");
-                    //     }
-                    // }
+    #[test]
+    fn evaluate_min_max() {
+        assert_eq!("1", Calculator::new().evaluate_calc("min(3, 1, 2)").unwrap());
+        assert_eq!("-1", Calculator::new().evaluate_calc("max(-3, -1, -2)").unwrap());
+        assert_eq!("5", Calculator::new().evaluate_calc("max(5)").unwrap());
+    }
 
-                    // this is the "just go up" approach
-                    let mut reverse_cursor_loc = cursor_loc;
-                    while !tree.matches_root(reverse_cursor_loc) {
-                        let parent_loc = tree.get_node_parent(reverse_cursor_loc);
-                        if parent_loc.is_none() {
-                            return Err("a node that isn't the root has no parent".to_string());
-                        }
-                        reverse_cursor_loc = parent_loc.unwrap();
-                        let open_paren_node = match tree.node_at(reverse_cursor_loc) {
-                            Some(n) => n,
-                            None => { return Err("no node exists at a node's parent's location".to_string()); }
-                        };
-                        match open_paren_node.value.token_type {
-                            // if the parent is an "(" then stop here
-                            CalcParseToken::OpenParen => {
-                                break;
-                            },
-                            CalcParseToken::Operator => {
-                                if !open_paren_node.has_left() || !open_paren_node.has_right() {
-                                    return Err("close paren not expected because previous operator node does not have two operands".to_string());
-                                }
-                            },
-                            CalcParseToken::Value | CalcParseToken::CloseParen => ()
-                        }
-                    }
-                    let open_paren_node = match tree.node_at_mut(reverse_cursor_loc) {
-                        Some(n) => n,
-                        None => { return Err("no node exists at found nearest open paren".to_string()); }
-                    };
-                    match open_paren_node.value.token_type {
-                        CalcParseToken::OpenParen => {
-                            // change open paren to a close paren
-                            open_paren_node.value = CalcEvalItem::new(token_type, token);
-                            cursor = Some(reverse_cursor_loc);
-                        },
-                        _ => {
-                            // handle the case where we hit the root before finding an open paren
-                            return Err("no corresponding open paren token found for the new close paren".to_string());
-                        }
-                    }
-                }
-            }
-        }
-        return Ok(tree);
+    #[test]
+    fn evaluate_sum_avg() {
+        assert_eq!("6", Calculator::new().evaluate_calc("sum(1, 2, 3)").unwrap());
+        assert_eq!("1.5", Calculator::new().evaluate_calc("avg(1, 2)").unwrap());
     }
 
-    fn evaluate_calc(calc: &str) -> Result<String, String> {
-        let tree = match Calculator::build_calc_eval_tree(calc) {
-            Ok(t) => t,
-            Err(m) => { return Err(m); }
-        };
+    #[test]
+    fn evaluate_function_arguments_with_expressions() {
+        let result = Calculator::new().evaluate_calc("2 * max(1 + 2, 2 ^ 2, sqrt(9)) - 1").unwrap();
+        assert_eq!("7", result);
+    }
 
-        let mut eval_stack = Vec::<BigDecimal>::new();
-        let mut postorder = PostOrderIter::new(&tree);
-        while let Some(index) = postorder.next() {
-            let node = match tree.node_at(index) {
-                Some(n) => n,
-                None => { return Err("Node does not exist at given index".to_string()); }
-            };
-            let token_type = match CalcParseToken::get_token_matching_str(&node.value.string_value) {
-                Some(t) => t,
-                None => { return Err(format!("Unknown token type for token [{}]", &node.value.string_value)); }
-            };
-            if token_type == CalcParseToken::Value {
-                match BigDecimal::from_str(&node.value.string_value) {
-                    Ok(x) => { eval_stack.push(x); },
-                    Err(_) => { return Err(format!("Unable to parse value [{}] into a BigDecimal", &node.value.string_value)); }
-                }
-            } else if token_type == CalcParseToken::Operator {
-                if eval_stack.len() < 2 {
-                    return Err(format!("cannot perform operation [{}] with only one value on the stack", &node.value.string_value));
-                }
-                let val_right = eval_stack.pop().unwrap();
-                let val_left = eval_stack.pop().unwrap();
-                let result = match node.value.string_value.as_str() {
-                    "+" => val_left + val_right,
-                    "-" => val_left - val_right,
-                    "*" => val_left * val_right,
-                    "/" => val_left / val_right,
-                    _ => { return Err(format!("cannot perform unknown operation [{}]", &node.value.string_value)); }
-                };
-                eval_stack.push(result);
-            }
-        }
-        if eval_stack.len() > 1 {
-            return Err("Extraneous value(s) remain on the stack after perfoming the evaluation".to_string());
-        }
-        if eval_stack.len() == 0 {
-            return Err("No final result value is on the stack after perfoming the evaluation".to_string());
-        }
-        return Ok(eval_stack.pop().unwrap().to_string());
+    #[test]
+    fn evaluate_thousands_separator_within_function_arguments() {
+        let result = Calculator::new().evaluate_calc("max((1,000), 2)").unwrap();
+        assert_eq!("1000", result);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn evaluate_round_digits() {
+        assert_eq!("3.14", Calculator::new().evaluate_calc("round(3.14159, 2)").unwrap());
+        assert_eq!("-3.15", Calculator::new().evaluate_calc("round(-3.145, 2)").unwrap());
+        assert_eq!("1200", Calculator::new().evaluate_calc("round(1234, -2)").unwrap());
+    }
 
     #[test]
-    fn tokenize_empty() {
-        assert_eq!(Vec::<String>::new(), Calculator::parse_calc_to_tokens(""));
+    fn evaluate_round_digits_fractional() {
+        assert!(Calculator::new().evaluate_calc("round(3.14159, 1.5)").is_err());
     }
 
     #[test]
-    fn tokenize_only_space() {
-        assert_eq!(Vec::<String>::new(), Calculator::parse_calc_to_tokens("  "));
+    fn evaluate_atan2() {
+        assert_eq!("0.78539816339744830961566084581987572104929234984378", Calculator::new().evaluate_calc("atan2(1, 1)").unwrap());
+        assert_eq!("-2.3561944901923449288469825374596271631478770495313", Calculator::new().evaluate_calc("atan2(-1, -1)").unwrap());
+        assert_eq!("0", Calculator::new().evaluate_calc("atan2(0, 0)").unwrap());
     }
 
     #[test]
-    fn tokenize_operator() {
-        assert_eq!(vec!["1", "+", "2"], Calculator::parse_calc_to_tokens("1 + 2"));
+    fn evaluate_hypot() {
+        let result = Calculator::new().evaluate_calc("hypot(3, 4)").unwrap();
+        assert_eq!("5", result);
     }
 
     #[test]
-    fn tokenize_multichar_value() {
-        assert_eq!(vec!["123"], Calculator::parse_calc_to_tokens("123"));
+    fn evaluate_factorial() {
+        let calc = Calculator::new();
+        assert_eq!("120", calc.evaluate_calc("5!").unwrap());
+        assert_eq!("1", calc.evaluate_calc("0!").unwrap());
+        assert_eq!("-6", calc.evaluate_calc("-3!").unwrap());
+        assert_eq!("64", calc.evaluate_calc("2^3!").unwrap());
+        assert_eq!("720", calc.evaluate_calc("((1+2)!)!").unwrap());
+        assert!(calc.evaluate_calc("(1+2)!!").unwrap_err().contains("(n!)!"));
+        assert_eq!("815915283247897734345611269596115894272000000000", calc.evaluate_calc("40!").unwrap());
+        assert!(calc.evaluate_calc("2.5!").unwrap_err().contains("gamma"));
+        assert!(calc.evaluate_calc("(-1)!").is_err());
+        assert!(calc.evaluate_calc("100000!").unwrap_err().contains("too large"));
+        assert_eq!(Ok(rational(20, 1)), calc.evaluate_calc_exact("5!/3!"));
     }
 
     #[test]
-    fn tokenize_decimal() {
-        assert_eq!(vec!["123.456", "*", "0.789"], Calculator::parse_calc_to_tokens("123.456 * 0.789"));
+    fn evaluate_combinations() {
+        let calc = Calculator::new();
+        assert_eq!("2598960", calc.evaluate_calc("nCr(52, 5)").unwrap());
+        assert_eq!("720", calc.evaluate_calc("nPr(10, 3)").unwrap());
+        assert_eq!("0", calc.evaluate_calc("nCr(3, 5)").unwrap());
+        assert_eq!("1", calc.evaluate_calc("nCr(5, 0)").unwrap());
+        assert_eq!("100891344545564193334812497256", calc.evaluate_calc("nCr(100, 50)").unwrap());
+        assert_eq!("0", calc.evaluate_calc("nCr(10^1000, 1) - 10^1000").unwrap());
+        assert!(calc.evaluate_calc("nCr(5.5, 2)").is_err());
+        assert!(calc.evaluate_calc("nPr(10^50, 100000)").unwrap_err().contains("too large"));
     }
 
     #[test]
-    fn tokenize_negative_only() {
-        assert_eq!(vec!["-123.456"], Calculator::parse_calc_to_tokens("-123.456"));
+    fn evaluate_gcd_lcm() {
+        let calc = Calculator::new();
+        assert_eq!("6", calc.evaluate_calc("gcd(12, 18)").unwrap());
+        assert_eq!("4", calc.evaluate_calc("gcd(-8, 12, 20)").unwrap());
+        assert_eq!("36", calc.evaluate_calc("lcm(12, 18)").unwrap());
+        assert_eq!("0", calc.evaluate_calc("lcm(0, 5)").unwrap());
+        assert!(calc.evaluate_calc("gcd(1.5, 3)").is_err());
     }
 
     #[test]
-    fn tokenize_negative_value() {
-        assert_eq!(vec!["1", "-", "-1"], Calculator::parse_calc_to_tokens("1 - -1"));
+    fn evaluate_isprime() {
+        let calc = Calculator::new();
+        assert_eq!("1", calc.evaluate_calc("isprime(2)").unwrap());
+        assert_eq!("0", calc.evaluate_calc("isprime(1)").unwrap());
+        assert_eq!("0", calc.evaluate_calc("isprime(-7)").unwrap());
+        assert_eq!("1", calc.evaluate_calc("isprime(2^61 - 1)").unwrap());
+        assert_eq!("0", calc.evaluate_calc("isprime(561)").unwrap());
+        assert_eq!("0", calc.evaluate_calc("isprime(3215031751)").unwrap());
+        assert_eq!("1", calc.evaluate_calc("isprime(2^127 - 1)").unwrap());
+        assert_eq!("0", calc.evaluate_calc("isprime(10^299)").unwrap());
+        assert!(calc.evaluate_calc("isprime(10^300)").unwrap_err().contains("300 digits"));
     }
 
     #[test]
-    fn tokenize_paren_negative_value() {
-        assert_eq!(vec!["1", "-", "(", "-1", ")"], Calculator::parse_calc_to_tokens("1 - (-1)"));
+    fn prime_factors() {
+        let factors = |n: i64| bigmath::prime_factors(&BigInt::from(n)).unwrap()
+            .into_iter().map(|(p, e)| (p.to_string(), e)).collect::<Vec<(String, u32)>>();
+        assert_eq!(vec![("2".to_string(), 3), ("3".to_string(), 1), ("5".to_string(), 1)], factors(120));
+        assert_eq!(Vec::<(String, u32)>::new(), factors(1));
+        assert_eq!(vec![("1000003".to_string(), 1), ("1000033".to_string(), 1)], factors(1000003 * 1000033));
+        assert!(bigmath::prime_factors(&BigInt::zero()).is_err());
     }
 
     #[test]
-    fn tokenize_paren_then_subtract() {
-        assert_eq!(vec!["(", "1", ")", "-", "1"], Calculator::parse_calc_to_tokens("(1)-1"));
+    fn evaluate_modpow_modinv() {
+        let calc = Calculator::new();
+        assert_eq!("445", calc.evaluate_calc("modpow(4, 13, 497)").unwrap());
+        assert_eq!("4", calc.evaluate_calc("modinv(3, 11)").unwrap());
+        assert_eq!("4", calc.evaluate_calc("modpow(3, -1, 11)").unwrap());
+        assert_eq!("1", calc.evaluate_calc("modpow(2, 10^100, 3)").unwrap());
+        assert!(calc.evaluate_calc("modinv(6, 9)").unwrap_err().contains("no inverse"));
+        assert!(calc.evaluate_calc("modpow(2, 3, 0)").is_err());
+        assert_eq!(Ok(rational(2, 3)), calc.evaluate_calc_exact("gcd(12, 18) / modpow(3, 2, 100)"));
     }
 
     #[test]
-    fn tokenize_nested_paren() {
-        assert_eq!(vec!["(","(","1","*","2",")","*","3",")"], Calculator::parse_calc_to_tokens("((1*2)*3)"));
+    fn evaluate_comparisons() {
+        let calc = Calculator::new();
+        assert_eq!("1", calc.evaluate_calc("0.1 + 0.2 == 0.3").unwrap());
+        assert_eq!("1", calc.evaluate_calc("1.0 == 1").unwrap());
+        assert_eq!("0", calc.evaluate_calc("3 != 3").unwrap());
+        assert_eq!("1", calc.evaluate_calc("-1 < -0.5").unwrap());
+        assert_eq!("1", calc.evaluate_calc("2 >= 2").unwrap());
+        assert_eq!("0", calc.evaluate_calc("2^10 > 1024").unwrap());
+        assert_eq!("1", calc.evaluate_calc("5! == 120").unwrap());
+        assert_eq!(Ok(rational(1, 1)), calc.evaluate_calc_exact("1/3 + 1/3 == 2/3"));
+        assert!(rational(-1, 2) < rational(1, 3));
+        assert!(rational(1, 3) < rational(1, 2));
     }
 
     #[test]
-    fn tokenize_not_starting_nested_paren() {
-        assert_eq!(vec!["1","+","(","(","2",")",")"], Calculator::parse_calc_to_tokens("1+((2))"));
+    fn comparisons_are_exact_however_evaluated() {
+        let mut calc = Calculator::new();
+        assert_eq!("1", calc.evaluate_calc("1/3 + 1/3 == 2/3").unwrap());
+        assert_eq!(&CalcResult::Boolean(true), enter_calc_and_get_result(&mut calc, "b = 1/3 + 1/3 == 2/3"));
+        assert_eq!(Some(&BigDecimal::from(1)), calc.variables.get("b"));
+        enter_calc(&mut calc, "g(x) = if(x/3 + x/3 == 2*x/3, 1, 2)");
+        assert_eq!("1", calc.evaluate_calc("g(1)").unwrap());
+        // each side is calculated once, and its exact value is compared
+        enter_calc(&mut calc, "third(x) = x/3");
+        let tree = Calculator::build_calc_eval_tree("third(1) + third(1) == 2/3").unwrap();
+        let mut state = CalcEvalState::new(None);
+        assert!(!calc.evaluate_tree(&tree, &BTreeMap::new(), 0, &mut state).unwrap().is_zero());
+        assert_eq!(2, state.function_calls);
+        enter_calc(&mut calc, "digits 6");
+        assert_eq!(&CalcResult::Decimal(BigDecimal::from(1)), enter_calc_and_get_result(&mut calc, "if(1/3 + 1/3 == 2/3, 1, 2)"));
+        // in programmer mode, it's the wrapped values that are compared
+        let u8_word = CalcWordSize { bits: 8, signed: false };
+        calc.word_size = Some(u8_word);
+        assert_eq!(&CalcResult::Word(BigInt::from(0), u8_word, true), enter_calc_and_get_result(&mut calc, "200 + 100 > 50"));
     }
 
     #[test]
-    fn tokenize_double_plus() {
-        assert_eq!(vec!["1","+","+","2"], Calculator::parse_calc_to_tokens("1++2"));
+    fn evaluate_logical_operator_precedence() {
+        let calc = Calculator::new();
+        assert_eq!("1", calc.evaluate_calc("1 + 1 == 2 and 2 < 1 or 3 > 2").unwrap());
+        assert_eq!("1", calc.evaluate_calc("not 1 == 2").unwrap());
+        assert_eq!("0", calc.evaluate_calc("not 0 and 0").unwrap());
+        assert_eq!("1", calc.evaluate_calc("not (0 and 1)").unwrap());
+        assert_eq!("1", calc.evaluate_calc("2 and -3").unwrap());
+        assert_eq!("2", calc.evaluate_calc("(1 < 2) + (3 < 4)").unwrap());
     }
 
     #[test]
-    fn tokenize_double_times() {
-        assert_eq!(vec!["1","*","*","2"], Calculator::parse_calc_to_tokens("1**2"));
+    fn evaluate_if() {
+        let mut calc = Calculator::new();
+        assert_eq!("10", calc.evaluate_calc("if(1 < 2, 10, 20)").unwrap());
+        assert_eq!("5", calc.evaluate_calc("if(0, 1/0, 5)").unwrap());
+        assert_eq!("5", calc.evaluate_calc("if(1, 5, 1/0)").unwrap());
+        assert_eq!("7", calc.evaluate_calc("1 + if(2 > 1, if(0, 1/0, 6), 1/0)").unwrap());
+        assert!(calc.evaluate_calc("if(1, 2)").is_err());
+        assert!(calc.evaluate_calc("if(1 == 1, 1/0, 2)").is_err());
+        assert_eq!(Ok(rational(1, 3)), calc.evaluate_calc_exact("if(1/3 < 1/2, 1/3, 1/0)"));
+        enter_calc(&mut calc, "fact(n) = if(n <= 1, 1, n * fact(n - 1))");
+        assert_eq!("3628800", calc.evaluate_calc("fact(10)").unwrap());
+        assert!(Calculator::parse_assignment("if = 1").is_err());
     }
 
     #[test]
-    fn tokenize_double_divide() {
-        assert_eq!(vec!["1","/","/","2"], Calculator::parse_calc_to_tokens("1//2"));
+    fn evaluate_near() {
+        let calc = Calculator::new();
+        assert_eq!("1", calc.evaluate_calc("near(1, 1.0001, 0.001)").unwrap());
+        assert_eq!("0", calc.evaluate_calc("near(1, 1.01, 0.001)").unwrap());
+        assert_eq!("1", calc.evaluate_calc("near(sin(pi), 0)").unwrap());
+        assert_eq!("0", calc.evaluate_calc("sin(pi) == 0").unwrap());
+        assert_eq!("1", calc.evaluate_calc("near(exp(100), exp(50)^2)").unwrap());
+        assert!(calc.evaluate_calc("near(1, 2, -1)").is_err());
     }
 
     #[test]
-    fn tokenize_minus_plus() {
-        assert_eq!(vec!["1","-","+","2"], Calculator::parse_calc_to_tokens("1-+2"));
+    fn evaluate_wrong_argument_count() {
+        assert!(Calculator::new().evaluate_calc("atan2(1)").is_err());
+        assert!(Calculator::new().evaluate_calc("round(1, 2, 3)").is_err());
+        assert!(Calculator::new().evaluate_calc("sqrt(1, 2)").is_err());
     }
 
     #[test]
-    fn tokenize_multiply_divide() {
-        assert_eq!(vec!["1","*","/","2"], Calculator::parse_calc_to_tokens("1*/2"));
+    fn evaluate_unknown_function() {
+        assert!(Calculator::new().evaluate_calc("foo(2)").is_err());
     }
 
     #[test]
-    fn tokenize_triple_minus() {
-        assert_eq!(vec!["1","-","-","-2"], Calculator::parse_calc_to_tokens("1---2"));
+    fn evaluate_unknown_name() {
+        assert!(Calculator::new().evaluate_calc("foo+2").is_err());
     }
 
     #[test]
-    fn tree_add_minimal() {
-        let mut tree = Tree::<&str>::new();
+    fn perform_calculation_invalid_function_call() {
+        let mut calc = Calculator::new();
+        for invalid in ["foo(3)", "sqrt 2", "ln(0)"] {
+            let message = calc.evaluate_calc(invalid).unwrap_err();
+            assert_eq!(&CalcResult::Error(message), enter_calc_and_get_result(&mut calc, invalid));
+        }
+        assert_eq!(3, calc.prev_calcs.len());
+    }
 
-        let index_loc = tree.add_node(TreeNode::new("a"));
+    #[test]
+    fn evaluate_variable() {
+        let mut calc = Calculator::new();
+        calc.variables.insert("rate".to_string(), BigDecimal::from_str("0.0725").unwrap());
+        assert_eq!("7.25", calc.evaluate_calc("100*rate").unwrap());
+    }
 
-        assert_eq!(0, index_loc);
+    #[test]
+    fn assign_variable() {
+        let mut calc = Calculator::new();
+        calc.calc = String::from("rate = 0.0725");
+        calc.perform_calculation();
+        assert_eq!(Some(&BigDecimal::from_str("0.0725").unwrap()), calc.variables.get("rate"));
+        assert_eq!(("rate = 0.0725".to_string(), CalcResult::Decimal(BigDecimal::from_str("0.0725").unwrap())), calc.prev_calcs[0]);
+        assert!(calc.calc.is_empty());
     }
 
     #[test]
-    fn tree_add_a_few() {
-        let mut tree = Tree::<&str>::new();
+    fn assign_variable_keeps_exact_value() {
+        let mut calc = Calculator::new();
+        calc.calc = String::from("third = 1/3");
+        calc.perform_calculation();
+        let third = bigmath::divide(&BigDecimal::from(1), &BigDecimal::from(3), RoundingMode::HalfEven).unwrap();
+        assert_eq!(&third, calc.variables.get("third").unwrap());
+        // shown as a repeating decimal, so it's kept exactly, just like ans
+        assert_eq!("1", enter_calc_and_format(&mut calc, "third*3"));
+        enter_calc(&mut calc, "1/3");
+        assert_eq!("1", enter_calc_and_format(&mut calc, "ans*3"));
+    }
 
-        let a = tree.add_node(TreeNode::new("a"));
-        let b = tree.add_node(TreeNode::new("b"));
-        let c = tree.add_node_with_children(TreeNode::new("c"), Some(a), Some(b));
+    #[test]
+    fn assign_variable_overwrite_using_itself() {
+        let mut calc = Calculator::new();
+        calc.calc = String::from("count = 1");
+        calc.perform_calculation();
+        calc.calc = String::from("count = count + 1");
+        calc.perform_calculation();
+        assert_eq!("2", calc.evaluate_calc("count").unwrap());
+    }
 
-        assert_eq!(2, c);
+    #[test]
+    fn delete_variable() {
+        let mut calc = Calculator::new();
+        calc.calc = String::from("x = 5");
+        calc.perform_calculation();
+        calc.calc = String::from("x =");
+        calc.perform_calculation();
+        assert!(calc.variables.is_empty());
+        assert!(calc.evaluate_calc("x").is_err());
+        calc.calc = String::from("x =");
+        calc.perform_calculation();
+        assert!(matches!(calc.prev_calcs.last().unwrap().1, CalcResult::Error(_)));
     }
 
     #[test]
-    fn tree_postorder_empty() {
-        let tree = Tree::<&str>::new();
+    fn assign_variable_failed_expression() {
+        let mut calc = Calculator::new();
+        calc.calc = String::from("x = 1/0");
+        calc.perform_calculation();
+        assert!(calc.variables.is_empty());
+        assert!(matches!(calc.prev_calcs.last().unwrap().1, CalcResult::Error(_)));
+    }
 
-        let mut output = String::from("");
-        let mut postorder = PostOrderIter::new(&tree);
-        while let Some(index) = postorder.next() {
-            let node = tree.node_at(index).expect("Node does not exist at given index");
-            output.push_str(&node.value.to_string());
-        }
-        assert_eq!("", output);
+    #[test]
+    fn parse_assignment_invalid() {
+        assert_eq!(Ok(None), Calculator::parse_assignment("1+2"));
+        assert_eq!(Ok(Some((CalcAssignment::Variable("x".to_string()), "1+2".to_string()))), Calculator::parse_assignment(" x = 1+2 "));
+        assert!(Calculator::parse_assignment("2x = 3").is_err());
+        assert!(Calculator::parse_assignment("x+1 = 3").is_err());
+        assert!(Calculator::parse_assignment("sqrt = 3").is_err());
+        assert!(Calculator::parse_assignment("x = y = 3").is_err());
     }
 
     #[test]
-    fn tree_postorder_index_minimal() {
-        let mut tree = Tree::<&str>::new();
+    fn evaluate_ans() {
+        let mut calc = Calculator::new();
+        assert!(calc.evaluate_calc("ans").is_err());
+        calc.prev_calcs.push(("6*7".to_string(), CalcResult::Decimal(BigDecimal::from(42))));
+        calc.prev_calcs.push(("1/0".to_string(), CalcResult::Error("cannot divide by zero".to_string())));
+        assert_eq!("84", calc.evaluate_calc("ans*2").unwrap());
+    }
 
-        let a = tree.add_node(TreeNode::new("a"));
-        tree.set_root(Some(a));
+    #[test]
+    fn evaluate_history_references() {
+        let mut calc = Calculator::new();
+        calc.prev_calcs.push(("1/4".to_string(), CalcResult::Float(0.25)));
+        calc.prev_calcs.push(("6*7".to_string(), CalcResult::Decimal(BigDecimal::from(42))));
+        calc.prev_calcs.push(("1/0".to_string(), CalcResult::Error("cannot divide by zero".to_string())));
+        assert_eq!("42.25", calc.evaluate_calc("#2 + #3").unwrap());
+        assert!(calc.evaluate_calc("#1").is_err());
+        assert!(calc.evaluate_calc("#0").is_err());
+        assert!(calc.evaluate_calc("#4").is_err());
+    }
 
-        let mut postorder = PostOrderIter::new(&tree);
-        let index_loc = postorder.next().expect("Node does not exist at given index");
+    #[test]
+    fn assign_ans_is_not_allowed() {
+        assert!(Calculator::parse_assignment("ans = 3").is_err());
+        assert!(Calculator::parse_assignment("#1 = 3").is_err());
+    }
 
-        assert_eq!(0, index_loc);
+    #[test]
+    fn parse_assignment_function() {
+        assert_eq!(Ok(Some((CalcAssignment::Function("f".to_string(), vec!["x".to_string()]), "x^2 + 1".to_string()))), Calculator::parse_assignment("f(x) = x^2 + 1"));
+        assert_eq!(Ok(Some((CalcAssignment::Function("tip".to_string(), vec!["amount".to_string(), "pct".to_string()]), "".to_string()))), Calculator::parse_assignment("tip(amount, pct) ="));
+        assert!(Calculator::parse_assignment("f() = 1").is_err());
+        assert!(Calculator::parse_assignment("f(x, x) = x").is_err());
+        assert!(Calculator::parse_assignment("f(x = x").is_err());
+        assert!(Calculator::parse_assignment("f(2) = 2").is_err());
+        assert!(Calculator::parse_assignment("sqrt(x) = x").is_err());
     }
 
     #[test]
-    fn tree_postorder_minimal() {
-        let mut tree = Tree::<&str>::new();
+    fn parse_assignment_with_comparison() {
+        assert_eq!(Ok(None), Calculator::parse_assignment("x == 2"));
+        assert_eq!(Ok(None), Calculator::parse_assignment("x <= 2 or x != 5"));
+        assert_eq!(Ok(Some((CalcAssignment::Variable("same".to_string()), "x == y".to_string()))), Calculator::parse_assignment("same = x == y"));
+        assert!(Calculator::parse_assignment("x = y >= 1 = 3").is_err());
+    }
 
-        let a = tree.add_node(TreeNode::new("a"));
-        tree.set_root(Some(a));
+    #[test]
+    fn evaluate_user_function() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "f(x) = x^2 + 1");
+        enter_calc(&mut calc, "tip(amount, pct) = amount * pct / 100");
+        assert!(calc.prev_calcs.is_empty());
+        assert_eq!("10", calc.evaluate_calc("f(3)").unwrap());
+        assert_eq!("26", calc.evaluate_calc("f(f(2))").unwrap());
+        assert_eq!("9", calc.evaluate_calc("tip(60, 15)").unwrap());
+        assert_eq!("f(x) = x^2 + 1   tip(amount, pct) = amount * pct / 100", calc.format_user_functions());
+    }
 
-        let mut output = String::from("");
-        let mut postorder = PostOrderIter::new(&tree);
-        while let Some(index) = postorder.next() {
-            let node = tree.node_at(index).expect("Node does not exist at given index");
-            output.push_str(&node.value.to_string());
-        }
-        assert_eq!("a", output);
+    #[test]
+    fn evaluate_user_function_parameter_hides_variable() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "x = 100");
+        enter_calc(&mut calc, "y = 5");
+        enter_calc(&mut calc, "f(x) = x + y");
+        assert_eq!("6", calc.evaluate_calc("f(1)").unwrap());
+        enter_calc(&mut calc, "y = 7");
+        assert_eq!("8", calc.evaluate_calc("f(1)").unwrap());
     }
 
-    // example tree from https://en.wikipedia.org/wiki/Tree_traversal#Post-order_(LRN)
     #[test]
-    fn tree_postorder_complex() {
-        let mut tree = Tree::<&str>::new();
+    fn redefine_and_delete_user_function() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "f(x) = x + 1");
+        enter_calc(&mut calc, "f(x) = x + 2");
+        assert_eq!("3", calc.evaluate_calc("f(1)").unwrap());
+        enter_calc(&mut calc, "f(x) =");
+        assert!(calc.user_functions.is_empty());
+        assert!(calc.evaluate_calc("f(1)").is_err());
+        enter_calc(&mut calc, "f(x) =");
+        assert!(matches!(calc.prev_calcs.last().unwrap().1, CalcResult::Error(_)));
+    }
 
-        let a = tree.add_node(TreeNode::new("a"));
-        let c = tree.add_node(TreeNode::new("c"));
-        let e = tree.add_node(TreeNode::new("e"));
-        let d = tree.add_node_with_children(TreeNode::new("d"), Some(c), Some(e));
-        let b = tree.add_node_with_children(TreeNode::new("b"), Some(a), Some(d));
-        let h = tree.add_node(TreeNode::new("h"));
-        let i = tree.add_node_with_children(TreeNode::new("i"), Some(h), None);
-        let g = tree.add_node_with_children(TreeNode::new("g"), None, Some(i));
-        let f = tree.add_node_with_children(TreeNode::new("f"), Some(b), Some(g));
-        tree.set_root(Some(f));
+    #[test]
+    fn evaluate_user_function_wrong_argument_count() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "f(x, y) = x + y");
+        assert!(calc.evaluate_calc("f(1)").is_err());
+        assert!(calc.evaluate_calc("f(1, 2, 3)").is_err());
+    }
 
-        let mut output = String::from("");
-        let mut postorder = PostOrderIter::new(&tree);
-        while let Some(index) = postorder.next() {
-            let node = tree.node_at(index).expect("Node does not exist at given index");
-            output.push_str(&node.value.to_string());
-        }
-        assert_eq!("acedbhigf", output);
+    #[test]
+    fn evaluate_user_function_recursion_limit() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "f(x) = f(x - 1) + 1");
+        assert!(calc.evaluate_calc("f(3)").unwrap_err().contains("nested function calls"));
+    }
+
+    #[test]
+    fn evaluate_user_function_call_limit() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "f(n) = if(n <= 0, 1, f(n-1) + f(n-1))");
+        assert_eq!("32", calc.evaluate_calc("f(5)").unwrap());
+        assert!(calc.evaluate_calc("f(16)").unwrap_err().contains("more than 10000 function calls"));
+    }
+
+    #[test]
+    fn define_user_function_invalid_body() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "f(x) = x + * 1");
+        assert!(calc.user_functions.is_empty());
+        assert!(matches!(calc.prev_calcs.last().unwrap().1, CalcResult::Error(_)));
+    }
+
+    #[test]
+    fn perform_calculation_with_user_function() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "f(x) = x * 2");
+        assert_eq!(&CalcResult::Decimal(BigDecimal::from(42)), enter_calc_and_get_result(&mut calc, "f(21)"));
+        assert_eq!("f(21)", calc.prev_calcs[0].0);
+    }
+
+    #[test]
+    fn evaluate_constants() {
+        let calc = Calculator::new();
+        assert_eq!("6.2831853071795864769252867665590057683943387987502", calc.evaluate_calc("2*pi").unwrap());
+        assert_eq!(calc.evaluate_calc("tau").unwrap(), calc.evaluate_calc("2*pi").unwrap());
+        assert_eq!("1.0471975511965977461542144610931676280657231331250", calc.evaluate_calc("1/3*pi").unwrap());
+        assert_eq!("2.7182818284590452353602874713526624977572470937000", calc.evaluate_calc("e").unwrap());
+        assert_eq!("0.000000000000000000000000000000000662607015", calc.evaluate_calc("h").unwrap());
+        assert_eq!("602214076000000000000000", calc.evaluate_calc("NA").unwrap());
+        assert_eq!("299792458", calc.evaluate_calc("c").unwrap());
+    }
+
+    #[test]
+    fn assign_constant_is_not_allowed() {
+        assert!(Calculator::parse_assignment("pi = 3").is_err());
+        assert!(Calculator::parse_assignment("area(b, h) = b*h/2").is_ok());
+    }
+
+    #[test]
+    fn evaluate_user_function_parameter_hides_constant() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "area(b, h) = b*h/2");
+        assert_eq!("6", calc.evaluate_calc("area(3, 4)").unwrap());
+    }
+
+    #[test]
+    fn evaluate_implicit_multiplication() {
+        let mut calc = Calculator::new();
+        assert_eq!("14", calc.evaluate_calc("2(3+4)").unwrap());
+        assert_eq!("21", calc.evaluate_calc("(1+2)(3+4)").unwrap());
+        assert_eq!("6.2831853071795864769252867665590057683943387987502", calc.evaluate_calc("2pi").unwrap());
+        assert_eq!("4", calc.evaluate_calc("sqrt(4)(2)").unwrap());
+        enter_calc(&mut calc, "x = 5");
+        assert_eq!("11", calc.evaluate_calc("2x+1").unwrap());
+    }
+
+    #[test]
+    fn evaluate_implicit_multiplication_precedence() {
+        let calc = Calculator::new();
+        assert_eq!("1.5", calc.evaluate_calc("1/2(3)").unwrap());
+        assert_eq!("16", calc.evaluate_calc("2^3(2)").unwrap());
+        // with a decimal point, digits in parens repeat instead
+        assert_eq!(Ok(rational(137, 90)), calc.evaluate_calc_exact("1.5(2)"));
+        assert_eq!("3", calc.evaluate_calc("1.5 (2)").unwrap());
+    }
+
+    #[test]
+    fn rational_arithmetic() {
+        assert_eq!(rational(1, 2), rational(1, 3).add(&rational(1, 6)));
+        assert_eq!(rational(-1, 3), rational(2, -6));
+        assert_eq!(rational(1, 1), rational(1, 3).mul(&rational(3, 1)));
+        assert_eq!(rational(-2, 1), rational(-7, 3).floor_div(&rational(2, 1)).unwrap());
+        assert_eq!(rational(5, 3), rational(-7, 3).floor_mod(&rational(2, 1)).unwrap());
+        assert_eq!(rational(9, 4), rational(2, 3).pow(&rational(-2, 1)).unwrap());
+        assert_eq!(rational(3, 8), Rational::from_bigdecimal(&BigDecimal::from_str("0.375").unwrap()));
+        assert!(rational(1, 3).div(&rational(0, 1)).is_err());
+        assert!(rational(2, 1).pow(&rational(1, 2)).is_err());
+    }
+
+    #[test]
+    fn evaluate_exact() {
+        let calc = Calculator::new();
+        assert_eq!(Ok(rational(1, 1)), calc.evaluate_calc_exact("(1/3)*3"));
+        assert_eq!(Ok(rational(7, 12)), calc.evaluate_calc_exact("1/3 + 1/4"));
+        assert_eq!(Ok(rational(23, 20)), calc.evaluate_calc_exact("1 + 15%"));
+        assert_eq!(Ok(rational(1, 8)), calc.evaluate_calc_exact("2^-3"));
+        assert_eq!(Ok(rational(2, 3)), calc.evaluate_calc_exact("(6 & 3)/3"));
+        assert!(calc.evaluate_calc_exact("pi/2").is_err());
+        assert!(calc.evaluate_calc_exact("sqrt(4)/2").is_err());
+        assert!(calc.evaluate_calc_exact("1/(3-3)").is_err());
+    }
+
+    #[test]
+    fn parse_repeating_decimal() {
+        assert_eq!(Some(rational(1, 3)), CalcParseToken::parse_repeating_decimal("0.(3)"));
+        assert_eq!(Some(rational(-611, 495)), CalcParseToken::parse_repeating_decimal("-1.2(34)"));
+        assert_eq!(Some(rational(1, 6)), CalcParseToken::parse_repeating_decimal(".1(6)"));
+        assert_eq!(None, CalcParseToken::parse_repeating_decimal("0.()"));
+        assert_eq!(None, CalcParseToken::parse_repeating_decimal("1(3)"));
+    }
+
+    #[test]
+    fn rational_repeating_digits() {
+        assert!(rational(3, 40).has_terminating_decimal());
+        assert!(!rational(1, 7).has_terminating_decimal());
+        assert_eq!(Some(("".to_string(), "142857".to_string())), rational(1, 7).fraction_digits(100));
+        assert_eq!(Some(("1".to_string(), "6".to_string())), rational(7, 6).fraction_digits(100));
+        assert_eq!(Some(("375".to_string(), "".to_string())), rational(3, 8).fraction_digits(100));
+        assert_eq!(None, rational(1, 7).fraction_digits(5));
+    }
+
+    #[test]
+    fn rational_continued_fraction() {
+        let terms: Vec<BigInt> = vec![3, 7, 16].into_iter().map(BigInt::from).collect();
+        assert_eq!(terms, rational(355, 113).continued_fraction());
+        assert_eq!(vec![rational(3, 1), rational(22, 7), rational(355, 113)], rational(355, 113).convergents());
+        assert_eq!(vec![rational(-1, 1), rational(-2, 3)], rational(-2, 3).convergents());
     }
 
     #[test]
-    fn get_char_token_digit() {
-        for c in "0123456789".chars() {
-            assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_char(c).unwrap());
+    fn rational_approximate() {
+        let pi = Rational::from_bigdecimal(&BigDecimal::from_str("3.14159265").unwrap());
+        assert_eq!(Ok(rational(355, 113)), pi.approximate(&rational(1, 1_000_000)));
+        assert_eq!(Ok(rational(22, 7)), pi.approximate(&rational(1, 100)));
+        assert_eq!(Ok(rational(3, 1)), pi.approximate(&rational(1, 5)));
+        assert_eq!(Ok(rational(-1, 16)), rational(-63, 1000).approximate(&rational(1, 1000)));
+        assert_eq!(Ok(rational(0, 1)), rational(1, 3).approximate(&rational(1, 2)));
+        assert!(pi.approximate(&rational(-1, 2)).is_err());
+        // the ratio of neighbouring Fibonacci numbers has a continued fraction
+        //   of all 1s, as long as it can be
+        let (mut a, mut b) = (BigInt::from(1), BigInt::from(1));
+        for _ in 0..2000 {
+            (a, b) = (b.clone(), a + b);
         }
+        let ratio = Rational::new(b, a).unwrap();
+        assert_eq!(Ok(ratio.clone()), ratio.approximate(&rational(0, 1)));
+        let too_long = Rational::new(BigInt::from(10).pow(1000) + 1, BigInt::from(3)).unwrap();
+        assert!(too_long.approximate(&rational(0, 1)).is_err());
+        let third = bigmath::divide(&BigDecimal::from(1), &BigDecimal::from(3), RoundingMode::HalfEven).unwrap();
+        assert_eq!(Ok(rational(1, 3)), Rational::from_rounded_bigdecimal(&third, bigmath::DIVISION_DIGITS));
     }
 
     #[test]
-    fn get_char_token_operator() {
-        for c in "+-*/".chars() {
-            assert_eq!(CalcParseToken::Operator, CalcParseToken::get_token_matching_char(c).unwrap());
-        }
+    fn evaluate_approx() {
+        let mut calc = Calculator::new();
+        assert_eq!("355/113", enter_calc_and_format(&mut calc, "approx(3.14159265, 1e-6)"));
+        assert_eq!("201/64", enter_calc_and_format(&mut calc, "approx(pi, 0.001)"));
+        assert_eq!("1/16", enter_calc_and_format(&mut calc, "approx(0.0627, 0.001)"));
+        assert_eq!("22", enter_calc_and_format(&mut calc, "approx(22/7, 0) * 7"));
+        assert_eq!("[approx] only works on fractions of up to 1000 digits",
+            enter_calc_and_format(&mut calc, "approx((2/3)^3000 + (5/7)^2000, 0) as fraction"));
+        assert!(matches!(enter_calc_and_get_result(&mut calc, "approx(1, -1)"), CalcResult::Error(_)));
     }
 
     #[test]
-    fn get_char_token_open_paren() {
-        assert_eq!(CalcParseToken::OpenParen, CalcParseToken::get_token_matching_char('(').unwrap());
+    fn perform_calculation_with_constants() {
+        let mut calc = Calculator::new();
+        assert!(matches!(enter_calc_and_get_result(&mut calc, "2*pi*e/phi + tau - g*c"), CalcResult::Decimal(_)));
+        assert_eq!(1, calc.prev_calcs.len());
     }
 
     #[test]
-    fn get_char_token_close_paren() {
-        assert_eq!(CalcParseToken::CloseParen, CalcParseToken::get_token_matching_char(')').unwrap());
+    fn perform_calculation_near_zero_with_constants() {
+        let mut calc = Calculator::new();
+        assert!(matches!(enter_calc_and_get_result(&mut calc, "sin(pi)"), CalcResult::Decimal(_)));
+        assert!(matches!(enter_calc_and_get_result(&mut calc, "cos(pi/2)"), CalcResult::Decimal(_)));
+        assert_eq!(2, calc.prev_calcs.len());
     }
 
     #[test]
-    fn get_char_token_invalid() {
-        for c in "abc!$,=".chars() {
-            assert_eq!(None, CalcParseToken::get_token_matching_char(c));
-        }
+    fn perform_calculation_unary_minus() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "x = 3");
+        assert_eq!(&CalcResult::Decimal(BigDecimal::from(-8)), enter_calc_and_get_result(&mut calc, "-x^2 + +1"));
+        assert_eq!("-4", enter_calc_and_format(&mut calc, "-(2+2) as fraction"));
     }
 
     #[test]
-    fn get_str_token_digit() {
-        for c in "0123456789".chars() {
-            assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str(&String::from(c)).unwrap());
-        }
+    fn perform_calculation_keeps_exact_results() {
+        let mut calc = Calculator::new();
+        assert_eq!("9999999999800000000001", enter_calc_and_format(&mut calc, "99999999999 * 99999999999"));
+        assert_eq!("0.3", enter_calc_and_format(&mut calc, "0.1 + 0.2"));
+        assert_eq!("3.000000000000000000003", enter_calc_and_format(&mut calc, "1.000000000000000000001 * 3"));
+        assert_eq!(format!("1{}1", "0".repeat(399)), enter_calc_and_format(&mut calc, "10^400 + 1"));
     }
 
     #[test]
-    fn get_str_token_operator() {
-        for c in "+-*/".chars() {
-            assert_eq!(CalcParseToken::Operator, CalcParseToken::get_token_matching_str(&String::from(c)).unwrap());
-        }
+    fn ans_is_exact() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "2^70");
+        assert_eq!("1180591620717411303425", enter_calc_and_format(&mut calc, "ans + 1"));
     }
 
     #[test]
-    fn get_str_token_open_paren() {
-        assert_eq!(CalcParseToken::OpenParen, CalcParseToken::get_token_matching_str("(").unwrap());
+    fn recall_exact_result() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "12345678901234567890 * 10 + 0.5");
+        enter_calc(&mut calc, "2^64 in hex");
+        assert_eq!("123456789012345678900.5", recall_result(&mut calc, 2));
+        assert_eq!("0x10000000000000000", recall_result(&mut calc, 1));
     }
 
     #[test]
-    fn get_str_token_close_paren() {
-        assert_eq!(CalcParseToken::CloseParen, CalcParseToken::get_token_matching_str(")").unwrap());
+    fn split_output_base() {
+        assert_eq!(Ok(("255".to_string(), Some(16))), Calculator::split_output_base("255 in hex"));
+        assert_eq!(Ok(("0xFF + 1".to_string(), Some(2))), Calculator::split_output_base("0xFF + 1 to bin"));
+        assert_eq!(Ok(("35".to_string(), Some(36))), Calculator::split_output_base("35 in base 36"));
+        assert_eq!(Ok(("".to_string(), Some(8))), Calculator::split_output_base("in oct"));
+        assert_eq!(Ok(("1 + 2".to_string(), None)), Calculator::split_output_base("1 + 2"));
+        assert!(Calculator::split_output_base("35 in base 37").is_err());
+        assert!(Calculator::split_output_base("35 in base 1").is_err());
+        assert_eq!(Err("the output base is given twice: only one \"in hex\" (or similar) is allowed".to_string()),
+            Calculator::split_output_base("1 in hex in hex"));
+        assert!(Calculator::split_output_base("1 in base 8 to bin").is_err());
     }
 
     #[test]
-    fn get_str_token_integer() {
-        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str("123").unwrap());
+    fn format_prev_calculation_in_base() {
+        assert_eq!("0xFF", Calculator::format_prev_calculation_in_base(&CalcResult::Decimal(BigDecimal::from(255)), 16));
+        assert_eq!("-0b101", Calculator::format_prev_calculation_in_base(&CalcResult::Decimal(BigDecimal::from(-5)), 2));
+        assert_eq!("0o755", Calculator::format_prev_calculation_in_base(&CalcResult::Decimal(BigDecimal::from(493)), 8));
+        assert_eq!("Z (base 36)", Calculator::format_prev_calculation_in_base(&CalcResult::Decimal(BigDecimal::from(35)), 36));
+        assert_eq!("0x3", Calculator::format_prev_calculation_in_base(&CalcResult::Float(3.0), 16));
+        assert_eq!("1.5 (not an integer, so shown in decimal)", Calculator::format_prev_calculation_in_base(&CalcResult::Float(1.5), 16));
     }
 
     #[test]
-    fn get_str_token_negative_integer() {
-        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str("-456").unwrap());
+    fn perform_calculation_in_base() {
+        let mut calc = Calculator::new();
+        assert_eq!(&CalcResult::Decimal(BigDecimal::from(255)), enter_calc_and_get_result(&mut calc, "0xF0 + 15 in hex"));
+        assert_eq!(16, calc.get_display_base(&calc.prev_calcs[0].0));
+        assert_eq!(10, calc.output_base);
+        assert!(matches!(enter_calc_and_get_result(&mut calc, "1 in hex in hex"), CalcResult::Error(_)));
     }
 
     #[test]
-    fn get_str_token_decimal() {
-        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str("3.14").unwrap());
+    fn set_session_output_base() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "in bin");
+        assert!(calc.prev_calcs.is_empty());
+        assert_eq!(2, calc.output_base);
+        assert_eq!(2, calc.get_display_base("5"));
+        assert_eq!(10, calc.get_display_base("5 in dec"));
     }
 
     #[test]
-    fn get_str_token_negative_decimal() {
-        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str("-3.14").unwrap());
+    fn cycle_output_base() {
+        let mut calc = Calculator::new();
+        calc.cycle_output_base();
+        assert_eq!(16, calc.output_base);
+        calc.cycle_output_base();
+        calc.cycle_output_base();
+        assert_eq!(2, calc.output_base);
+        calc.cycle_output_base();
+        assert_eq!(10, calc.output_base);
+        calc.output_base = 36;
+        calc.cycle_output_base();
+        assert_eq!(10, calc.output_base);
     }
 
     #[test]
-    fn get_str_token_zero_decimal() {
-        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str("0.42").unwrap());
+    fn recall_result_in_base() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "255 in hex");
+        enter_calc(&mut calc, "35 in base 36");
+        assert_eq!("0xFF", recall_result(&mut calc, 2));
+        assert_eq!("255", calc.evaluate_calc(&calc.calc).unwrap());
+        assert_eq!("35", recall_result(&mut calc, 1));
     }
 
     #[test]
-    fn get_str_token_negative_zero_decimal() {
-        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str("-0.42").unwrap());
+    fn programmer_mode_wraps_and_flags_overflow() {
+        let mut calc = Calculator::new();
+        let u8_word = CalcWordSize { bits: 8, signed: false };
+        let i8_word = CalcWordSize { bits: 8, signed: true };
+        calc.word_size = Some(u8_word);
+        assert_eq!(&CalcResult::Word(BigInt::from(255), u8_word, false), enter_calc_and_get_result(&mut calc, "200 + 55"));
+        assert_eq!(&CalcResult::Word(BigInt::from(44), u8_word, true), enter_calc_and_get_result(&mut calc, "200 + 100"));
+        assert_eq!(&CalcResult::Word(BigInt::from(255), u8_word, true), enter_calc_and_get_result(&mut calc, "-1"));
+        calc.word_size = Some(i8_word);
+        assert_eq!(&CalcResult::Word(BigInt::from(-128), i8_word, true), enter_calc_and_get_result(&mut calc, "127 + 1"));
+        assert_eq!(&CalcResult::Word(BigInt::from(-1), i8_word, true), enter_calc_and_get_result(&mut calc, "0xFF"));
+        assert_eq!(&CalcResult::Word(BigInt::from(-128), i8_word, false), enter_calc_and_get_result(&mut calc, "-128"));
+        assert_eq!(&CalcResult::Word(BigInt::from(-128), i8_word, false), enter_calc_and_get_result(&mut calc, "-(128)"));
+        assert_eq!(&CalcResult::Word(BigInt::from(-128), i8_word, false), enter_calc_and_get_result(&mut calc, "-((128))"));
+        assert_eq!(&CalcResult::Word(BigInt::from(-128), i8_word, true), enter_calc_and_get_result(&mut calc, "(128)"));
     }
 
     #[test]
-    fn get_str_token_just_decimal() {
-        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str(".25").unwrap());
+    fn programmer_mode_flags_overflow_partway() {
+        let mut calc = Calculator::new();
+        let u8_word = CalcWordSize { bits: 8, signed: false };
+        calc.word_size = Some(u8_word);
+        assert_eq!(&CalcResult::Word(BigInt::from(200), u8_word, true), enter_calc_and_get_result(&mut calc, "200 + 100 - 100"));
+        enter_calc(&mut calc, "f(x) = x * 2");
+        assert_eq!(&CalcResult::Word(BigInt::from(200), u8_word, true), enter_calc_and_get_result(&mut calc, "f(200) - 200"));
+        assert_eq!(&CalcResult::Word(BigInt::from(100), u8_word, true), enter_calc_and_get_result(&mut calc, "100 - 200 + 200"));
     }
 
     #[test]
-    fn get_str_token_contains_open_paren() {
-        assert_eq!(None, CalcParseToken::get_token_matching_str("(10"));
+    fn programmer_mode_64_bits() {
+        let mut calc = Calculator::new();
+        calc.word_size = Some(CalcWordSize { bits: 64, signed: false });
+        // 2^64 itself doesn't fit, so "2^64 - 1" would overflow partway
+        assert_eq!("18446744073709551615 | 0xFFFF_FFFF_FFFF_FFFF | 0b1111_1111_1111_1111_1111_1111_1111_1111_1111_1111_1111_1111_1111_1111_1111_1111",
+            enter_calc_and_format(&mut calc, "2^63 - 1 + 2^63"));
+        assert_eq!("0 | 0x0000_0000_0000_0000 | 0b0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000_0000 (overflow: wrapped around to fit u64)",
+            enter_calc_and_format(&mut calc, "ans + 1"));
     }
 
     #[test]
-    fn get_str_token_contains_close_paren() {
-        assert_eq!(None, CalcParseToken::get_token_matching_str("10)"));
+    fn programmer_mode_needs_integers() {
+        let mut calc = Calculator::new();
+        calc.toggle_programmer_mode();
+        assert!(matches!(enter_calc_and_get_result(&mut calc, "1/2"), CalcResult::Error(_)));
+        assert_eq!(&CalcResult::Word(BigInt::from(3), CalcWordSize { bits: 64, signed: true }, false), enter_calc_and_get_result(&mut calc, "6/2"));
     }
 
     #[test]
-    fn get_str_token_negative_just_decimal() {
-        assert_eq!(CalcParseToken::Value, CalcParseToken::get_token_matching_str("-.25").unwrap());
+    fn format_word_result() {
+        let i8_word = CalcWordSize { bits: 8, signed: true };
+        let result = CalcResult::Word(BigInt::from(-1), i8_word, false);
+        assert_eq!("-1 | 0xFF | 0b1111_1111", Calculator::format_prev_calculation(&result));
+        assert_eq!("-1 | 0xFF | 0b1111_1111", Calculator::format_prev_calculation_in_base(&result, 16));
+        let result = CalcResult::Word(BigInt::from(44), CalcWordSize { bits: 16, signed: false }, true);
+        assert_eq!("44 | 0x002C | 0b0000_0000_0010_1100 (overflow: wrapped around to fit u16)", Calculator::format_prev_calculation(&result));
     }
 
     #[test]
-    fn get_str_token_two_decimals() {
-        assert_eq!(None, CalcParseToken::get_token_matching_str("5.2.5"));
+    fn format_bit_grid() {
+        assert_eq!(vec![" 7  0010 1100  0"],
+            Calculator::format_bit_grid(&BigInt::from(44), CalcWordSize { bits: 8, signed: false }));
+        assert_eq!(vec!["31  1111 1111 1111 1111  16", "15  1111 1111 1111 1110  0"],
+            Calculator::format_bit_grid(&BigInt::from(-2), CalcWordSize { bits: 32, signed: true }));
     }
 
     #[test]
-    fn get_str_token_negative_two_decimals() {
-        assert_eq!(None, CalcParseToken::get_token_matching_str("-.2.5"));
+    fn bit_grid_follows_selection() {
+        let mut calc = Calculator::new();
+        calc.word_size = Some(CalcWordSize { bits: 8, signed: false });
+        enter_calc(&mut calc, "1");
+        enter_calc(&mut calc, "2");
+        enter_calc(&mut calc, "1/0");
+        assert_eq!(Some(&BigInt::from(2)), calc.get_bit_grid_result().map(|r| r.0));
+        calc.selected_calc = 3;
+        assert_eq!(Some(&BigInt::from(1)), calc.get_bit_grid_result().map(|r| r.0));
+        calc.selected_calc = 1;
+        assert_eq!(None, calc.get_bit_grid_result());
     }
 
     #[test]
-    fn get_str_token_two_negatives() {
-        assert_eq!(None, CalcParseToken::get_token_matching_str("-2-5"));
+    fn change_word_size() {
+        let mut calc = Calculator::new();
+        calc.cycle_word_bits();
+        assert_eq!(None, calc.word_size);
+        calc.toggle_programmer_mode();
+        assert_eq!(Some(CalcWordSize { bits: 64, signed: true }), calc.word_size);
+        calc.cycle_word_bits();
+        calc.toggle_word_signed();
+        assert_eq!(Some(CalcWordSize { bits: 8, signed: false }), calc.word_size);
+        assert_eq!("u8", Calculator::get_word_size_name(calc.word_size.unwrap()));
+        calc.toggle_programmer_mode();
+        assert_eq!(None, calc.word_size);
     }
 
     #[test]
-    fn build_tree_add() {
-        let tree = Calculator::build_calc_eval_tree("123 + 456").unwrap();
-        let mut output = Vec::<&str>::new();
-        let mut postorder = PostOrderIter::new(&tree);
-        while let Some(index) = postorder.next() {
-            let node = tree.node_at(index).expect("Node does not exist at given index");
-            output.push(&node.value.string_value);
-        }
-        assert_eq!(vec!["123","456","+"], output);
+    fn format_decimal() {
+        assert_eq!("3", Calculator::format_decimal(&BigDecimal::from_str("3.0").unwrap()));
+        assert_eq!("0.25", Calculator::format_decimal(&BigDecimal::from_str("0.2500").unwrap()));
+        assert_eq!("100", Calculator::format_decimal(&BigDecimal::from(100)));
+        assert_eq!("-0.5", Calculator::format_decimal(&BigDecimal::from_str("-0.50").unwrap()));
     }
 
     #[test]
-    fn build_tree_multiply() {
-        let tree = Calculator::build_calc_eval_tree("123 * 456").unwrap();
-        let mut output = Vec::<&str>::new();
-        let mut postorder = PostOrderIter::new(&tree);
-        while let Some(index) = postorder.next() {
-            let node = tree.node_at(index).expect("Node does not exist at given index");
-            output.push(&node.value.string_value);
-        }
-        assert_eq!(vec!["123","456","*"], output);
+    fn round_to_places_modes() {
+        let x = BigDecimal::from_str("2.345").unwrap();
+        assert_eq!("2.34", bigmath::round_to_places(&x, 2, RoundingMode::HalfEven).to_string());
+        assert_eq!("2.35", bigmath::round_to_places(&x, 2, RoundingMode::HalfUp).to_string());
+        assert_eq!("2.34", bigmath::round_to_places(&x, 2, RoundingMode::Down).to_string());
+        assert_eq!("2.35", bigmath::round_to_places(&x, 2, RoundingMode::Up).to_string());
+        let x = BigDecimal::from_str("-2.355").unwrap();
+        assert_eq!("-2.36", bigmath::round_to_places(&x, 2, RoundingMode::HalfEven).to_string());
+        assert_eq!("-2.35", bigmath::round_to_places(&x, 2, RoundingMode::Down).to_string());
+        assert_eq!("-2.36", bigmath::round_to_places(&x, 2, RoundingMode::Up).to_string());
+        let x = BigDecimal::from_str("-0.001").unwrap();
+        assert_eq!("-0.01", bigmath::round_to_places(&x, 2, RoundingMode::Up).to_string());
+        assert_eq!("2.5", bigmath::round_to_places(&BigDecimal::from_str("2.5").unwrap(), 2, RoundingMode::Up).to_string());
     }
 
     #[test]
-    fn build_tree_add_three() {
-        let tree = Calculator::build_calc_eval_tree("123 + 456 + 789").unwrap();
-        let mut output = Vec::<&str>::new();
-        let mut postorder = PostOrderIter::new(&tree);
-        while let Some(index) = postorder.next() {
-            let node = tree.node_at(index).expect("Node does not exist at given index");
-            output.push(&node.value.string_value);
-        }
-        assert_eq!(vec!["123","456","+","789","+"], output);
+    fn round_to_significant_digits() {
+        let x = BigDecimal::from_str("123456").unwrap();
+        assert_eq!("123000", Calculator::format_decimal(&bigmath::round_to_significant_digits(&x, 3, RoundingMode::HalfEven)));
+        let x = BigDecimal::from_str("0.00123456").unwrap();
+        assert_eq!("0.00123", bigmath::round_to_significant_digits(&x, 3, RoundingMode::HalfEven).to_string());
+        let x = BigDecimal::from_str("-9.996").unwrap();
+        assert_eq!("-10", Calculator::format_decimal(&bigmath::round_to_significant_digits(&x, 3, RoundingMode::HalfUp)));
     }
 
     #[test]
-    fn build_tree_add_then_multiply() {
-        let tree = Calculator::build_calc_eval_tree("123 + 456 * 789").unwrap();
-        let mut output = Vec::<&str>::new();
-        let mut postorder = PostOrderIter::new(&tree);
-        while let Some(index) = postorder.next() {
-            let node = tree.node_at(index).expect("Node does not exist at given index");
-            output.push(&node.value.string_value);
-        }
-        assert_eq!(vec!["123","456","789","*","+"], output);
+    fn divide_rounds_with_mode() {
+        let two_thirds_down = bigmath::divide(&BigDecimal::from(2), &BigDecimal::from(3), RoundingMode::Down).unwrap();
+        let two_thirds_even = bigmath::divide(&BigDecimal::from(2), &BigDecimal::from(3), RoundingMode::HalfEven).unwrap();
+        assert_eq!(format!("0.{}", "6".repeat(100)), two_thirds_down.to_string());
+        assert_eq!(format!("0.{}7", "6".repeat(99)), two_thirds_even.to_string());
+        let big = bigmath::divide(&BigDecimal::from_str("-1e50").unwrap(), &BigDecimal::from(3), RoundingMode::Down).unwrap();
+        assert_eq!(format!("-{}.{}", "3".repeat(50), "3".repeat(50)), big.to_string());
+        assert_eq!("0.25", bigmath::divide(&BigDecimal::from(1), &BigDecimal::from(4), RoundingMode::Up).unwrap().to_string());
+        assert!(bigmath::divide(&BigDecimal::from(1), &BigDecimal::zero(), RoundingMode::Up).is_err());
     }
 
     #[test]
-    fn build_tree_add_multiply_add() {
-        let tree = Calculator::build_calc_eval_tree("1 + 2 * 3 + 4").unwrap();
-        let mut output = Vec::<&str>::new();
-        let mut postorder = PostOrderIter::new(&tree);
-        while let Some(index) = postorder.next() {
-            let node = tree.node_at(index).expect("Node does not exist at given index");
-            output.push(&node.value.string_value);
-        }
-        assert_eq!(vec!["1","2","3","*","+","4","+"], output);
+    fn rounding_settings() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "places 2");
+        assert_eq!("0.67", enter_calc_and_format(&mut calc, "2/3"));
+        enter_calc(&mut calc, "round down");
+        assert_eq!("0.66", enter_calc_and_format(&mut calc, "2/3"));
+        enter_calc(&mut calc, "digits 3");
+        assert_eq!("123000", enter_calc_and_format(&mut calc, "x = 123456"));
+        enter_calc(&mut calc, "digits off");
+        assert_eq!("123001", enter_calc_and_format(&mut calc, "x + 1"));
+        assert_eq!(None, calc.precision);
+        assert_eq!(RoundingMode::Down, calc.rounding_mode);
     }
 
     #[test]
-    fn build_tree_multiply_add_multiply() {
-        let tree = Calculator::build_calc_eval_tree("1 * 2 + 3 * 4").unwrap();
-        let mut output = Vec::<&str>::new();
-        let mut postorder = PostOrderIter::new(&tree);
-        while let Some(index) = postorder.next() {
-            let node = tree.node_at(index).expect("Node does not exist at given index");
-            output.push(&node.value.string_value);
-        }
-        assert_eq!(vec!["1","2","*","3","4","*","+"], output);
+    fn rounding_settings_bankers_rounding() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "places 2");
+        assert_eq!("0.38", enter_calc_and_format(&mut calc, "0.125 + 0.25"));
+        assert_eq!("0.14", enter_calc_and_format(&mut calc, "0.135"));
+        assert_eq!("rounding: 2 decimal places, half-even", calc.format_rounding());
     }
 
     #[test]
-    fn build_tree_multiply_multiply_multiply() {
-        let tree = Calculator::build_calc_eval_tree("1 * 2 * 3 * 4").unwrap();
-        let mut output = Vec::<&str>::new();
-        let mut postorder = PostOrderIter::new(&tree);
-        while let Some(index) = postorder.next() {
-            let node = tree.node_at(index).expect("Node does not exist at given index");
-            output.push(&node.value.string_value);
-        }
-        assert_eq!(vec!["1","2","3","4","*","*","*"], output);
+    fn rounding_settings_errors() {
+        let mut calc = Calculator::new();
+        assert!(matches!(enter_calc_and_get_result(&mut calc, "digits 0"), CalcResult::Error(_)));
+        assert!(matches!(enter_calc_and_get_result(&mut calc, "places 101"), CalcResult::Error(_)));
+        assert_eq!(None, calc.precision);
+        assert_eq!(None, Calculator::parse_rounding_setting("round(2.5)"));
+        assert_eq!(None, Calculator::parse_rounding_setting("digits 2 + 1"));
+        assert_eq!(None, Calculator::parse_rounding_setting("round sideways"));
     }
 
     #[test]
-    fn build_tree_paren_add() {
-        let tree = Calculator::build_calc_eval_tree("(1 + 2").unwrap();
-        let mut output = Vec::<&str>::new();
-        let mut postorder = PostOrderIter::new(&tree);
-        while let Some(index) = postorder.next() {
-            let node = tree.node_at(index).expect("Node does not exist at given index");
-            output.push(&node.value.string_value);
-        }
-        assert_eq!(vec!["1","2","+","("], output);
+    fn cycle_rounding_mode() {
+        let mut calc = Calculator::new();
+        calc.cycle_rounding_mode();
+        assert_eq!(RoundingMode::HalfUp, calc.rounding_mode);
+        calc.cycle_rounding_mode();
+        calc.cycle_rounding_mode();
+        calc.cycle_rounding_mode();
+        assert_eq!(RoundingMode::HalfEven, calc.rounding_mode);
     }
 
     #[test]
-    fn build_tree_two_parens_then_multiply() {
-        let tree = Calculator::build_calc_eval_tree("(1+2) + (3+4)*5").unwrap();
-        let mut output = Vec::<&str>::new();
-        let mut postorder = PostOrderIter::new(&tree);
-        while let Some(index) = postorder.next() {
-            let node = tree.node_at(index).expect("Node does not exist at given index");
-            output.push(&node.value.string_value);
-        }
-        assert_eq!(vec!["1","2","+",")","3","4","+",")","5","*","+"], output);
+    fn format_fraction() {
+        assert_eq!("7/3", Calculator::format_fraction(&rational(7, 3), CalcFractionFormat::Fraction));
+        assert_eq!("2 1/3", Calculator::format_fraction(&rational(7, 3), CalcFractionFormat::Mixed));
+        assert_eq!("-2 1/3", Calculator::format_fraction(&rational(-7, 3), CalcFractionFormat::Mixed));
+        assert_eq!("-1/3", Calculator::format_fraction(&rational(-1, 3), CalcFractionFormat::Mixed));
+        assert_eq!("0.375", Calculator::format_fraction(&rational(3, 8), CalcFractionFormat::Decimal));
+        assert_eq!("4", Calculator::format_fraction(&rational(8, 2), CalcFractionFormat::Fraction));
     }
 
     #[test]
-    fn build_tree_nested_parens() {
-        let tree = Calculator::build_calc_eval_tree("((1+2)*(3+4))/5").unwrap();
-        let mut output = Vec::<&str>::new();
-        let mut postorder = PostOrderIter::new(&tree);
-        while let Some(index) = postorder.next() {
-            let node = tree.node_at(index).expect("Node does not exist at given index");
-            output.push(&node.value.string_value);
-        }
-        assert_eq!(vec!["1","2","+",")","3","4","+",")","*",")","5","/"], output);
+    fn perform_calculation_as_fraction() {
+        let mut calc = Calculator::new();
+        assert_eq!("1/2", enter_calc_and_format(&mut calc, "2/3 * 3/4 as fraction"));
+        assert_eq!("2 1/3", enter_calc_and_format(&mut calc, "7/3 as mixed"));
+        assert_eq!("1", enter_calc_and_format(&mut calc, "(1/3)*3 as decimal"));
+        assert_eq!("constant [pi] cannot be calculated exactly", enter_calc_and_format(&mut calc, "1/3 * pi as fraction"));
+        assert!(matches!(enter_calc_and_get_result(&mut calc, "1/3 * pi as decimal"), CalcResult::Decimal(_)));
+        assert!(matches!(enter_calc_and_get_result(&mut calc, "(1/3)*3"), CalcResult::Decimal(_)));
+        assert_eq!(None, calc.rational_mode);
     }
 
     #[test]
-    fn build_tree_commas_spaces_and_dollar_signs() {
-        let tree = Calculator::build_calc_eval_tree("$1,000 * .25 + 22").unwrap();
-        let mut output = Vec::<&str>::new();
-        let mut postorder = PostOrderIter::new(&tree);
-        while let Some(index) = postorder.next() {
-            let node = tree.node_at(index).expect("Node does not exist at given index");
-            output.push(&node.value.string_value);
-        }
-        assert_eq!(vec!["1000",".25","*","22","+"], output);
+    fn rational_mode_for_all_results() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "as mixed");
+        assert_eq!(Some(CalcFractionFormat::Mixed), calc.rational_mode);
+        assert_eq!(&CalcResult::Fraction(rational(2, 3), CalcFractionFormat::Mixed), enter_calc_and_get_result(&mut calc, "scale = 2/3"));
+        assert_eq!("2 1/3", enter_calc_and_format(&mut calc, "(3 + 1/2) * scale"));
+        assert_eq!("7", enter_calc_and_format(&mut calc, "ans * 3"));
+        assert_eq!("7/3", enter_calc_and_format(&mut calc, "#2 as fraction"));
+        // a constant can't be kept exact, so it falls back to decimals
+        assert!(matches!(enter_calc_and_get_result(&mut calc, "1/3 * pi"), CalcResult::Decimal(_)));
+        enter_calc(&mut calc, "as decimal");
+        assert_eq!(None, calc.rational_mode);
+        enter_calc(&mut calc, "scale = 1/2");
+        assert_eq!(None, calc.exact_variables.get("scale"));
     }
 
     #[test]
-    fn evaluate_simple_add() {
-        let result = Calculator::evaluate_calc("1+1").unwrap();
-        assert_eq!("2", result);
+    fn recall_fraction_result() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "7/3 as mixed");
+        assert_eq!("7/3", recall_result(&mut calc, 1));
     }
 
     #[test]
-    fn evaluate_simple_subtract() {
-        let result = Calculator::evaluate_calc("1-2").unwrap();
-        assert_eq!("-1", result);
+    fn cycle_rational_mode() {
+        let mut calc = Calculator::new();
+        calc.cycle_rational_mode();
+        assert_eq!(Some(CalcFractionFormat::Fraction), calc.rational_mode);
+        calc.cycle_rational_mode();
+        calc.cycle_rational_mode();
+        assert_eq!(Some(CalcFractionFormat::Decimal), calc.rational_mode);
+        calc.cycle_rational_mode();
+        assert_eq!(None, calc.rational_mode);
     }
 
     #[test]
-    fn evaluate_simple_multiply() {
-        let result = Calculator::evaluate_calc("2*2").unwrap();
-        assert_eq!("4", result);
+    fn show_repeating_decimals() {
+        let mut calc = Calculator::new();
+        assert_eq!("0.(142857)", enter_calc_and_format(&mut calc, "1/7"));
+        assert_eq!("0.1(6)", enter_calc_and_format(&mut calc, "1/6"));
+        assert_eq!("-0.(3)", enter_calc_and_format(&mut calc, "-1/3"));
+        assert_eq!("1", enter_calc_and_format(&mut calc, "0.(3)*3"));
+        assert_eq!("0.25", enter_calc_and_format(&mut calc, "1/4"));
+        enter_calc(&mut calc, "digits 5");
+        assert_eq!("0.33333", enter_calc_and_format(&mut calc, "1/3"));
     }
 
     #[test]
-    fn evaluate_simple_divide() {
-        let result = Calculator::evaluate_calc("6/2").unwrap();
-        assert_eq!("3", result);
+    fn recall_repeating_decimal() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "4/3");
+        assert_eq!("1.(3)", recall_result(&mut calc, 1));
+        assert_eq!("4", enter_calc_and_format(&mut calc, "(1.(3)) * 3"));
     }
 
     #[test]
-    fn evaluate_nested_parens() {
-        let result = Calculator::evaluate_calc("((1+2)*(3+4))/7").unwrap();
-        assert_eq!("3", result);
+    fn show_convergents_of_selected_result() {
+        let mut calc = Calculator::new();
+        enter_calc(&mut calc, "pi");
+        enter_calc(&mut calc, "6");
+        assert_eq!(None, calc.get_convergents_result());
+        calc.selected_calc = 1;
+        assert_eq!(None, calc.get_convergents_result());
+        calc.selected_calc = 2;
+        let convergents = Calculator::format_convergents(&calc.get_convergents_result().unwrap());
+        assert!(convergents.starts_with("convergents: 3, 22/7, 333/106, 355/113, 103993/33102,"));
+        assert!(convergents.ends_with(", ..."));
+    }
+
+    #[test]
+    fn perform_calculation_combinations() {
+        let mut calc = Calculator::new();
+        assert_eq!(&CalcResult::Decimal(BigDecimal::from(1440)), enter_calc_and_get_result(&mut calc, "nCr(10, 3) * nPr(4, 2)"));
+        assert_eq!(&CalcResult::Decimal(BigDecimal::from(25)), enter_calc_and_get_result(&mut calc, "4! + 1"));
+    }
+
+    #[test]
+    fn perform_calculation_factor() {
+        let mut calc = Calculator::new();
+        assert_eq!("2^3 * 3^2 * 5", enter_calc_and_format(&mut calc, "factor(360)"));
+        assert_eq!("-2^2 * 3", enter_calc_and_format(&mut calc, "factor(-12)"));
+        assert_eq!("274177 * 67280421310721", enter_calc_and_format(&mut calc, "factor(2^64 + 1)"));
+        assert_eq!("13", enter_calc_and_format(&mut calc, "factor(12) + 1"));
+        assert_eq!("-347", enter_calc_and_format(&mut calc, "ans - #4"));
+        assert!(matches!(enter_calc_and_get_result(&mut calc, "factor(1/2)"), CalcResult::Error(_)));
+        assert_eq!("0x168", Calculator::format_prev_calculation_in_base(&calc.prev_calcs[0].1, 16));
+        assert_eq!("2^3 * 3^2 * 5", recall_result(&mut calc, 6));
+        assert_eq!("2^3 * 3^2 * 5", enter_calc_and_format(&mut calc, "factor(360) as fraction"));
+    }
+
+    #[test]
+    fn perform_calculation_boolean() {
+        let mut calc = Calculator::new();
+        assert_eq!(&CalcResult::Boolean(true), enter_calc_and_get_result(&mut calc, "1/3 + 1/3 == 2/3"));
+        assert_eq!(&CalcResult::Boolean(true), enter_calc_and_get_result(&mut calc, "(2 > 1)"));
+        assert_eq!(&CalcResult::Decimal(BigDecimal::from(2)), enter_calc_and_get_result(&mut calc, "(2 > 1) + 1"));
+        assert_eq!("true", Calculator::format_prev_calculation_in_base(enter_calc_and_get_result(&mut calc, "near(22/7, pi, 0.01) in hex"), 16));
+        assert_eq!(&CalcResult::Decimal(BigDecimal::from(2)), enter_calc_and_get_result(&mut calc, "ans + 1"));
+        assert_eq!("false", enter_calc_and_format(&mut calc, "1 > 2 as fraction"));
+        assert_eq!("0", recall_result(&mut calc, 1));
+    }
+
+    #[test]
+    fn perform_calculation_in_angle_mode() {
+        let mut calc = Calculator::new();
+        calc.cycle_angle_mode();
+        assert_eq!(AngleUnit::Degrees, calc.angle_mode);
+        enter_calc(&mut calc, "x = 60");
+        assert_eq!(&CalcResult::Decimal(BigDecimal::from_str("0.5").unwrap()), enter_calc_and_get_result(&mut calc, "cos(x°)"));
+        assert_eq!("90", enter_calc_and_format(&mut calc, "asin(1)"));
+        assert_eq!(3, calc.prev_calcs.len());
+        calc.cycle_angle_mode();
+        calc.cycle_angle_mode();
+        assert_eq!(AngleUnit::Radians, calc.angle_mode);
+    }
+
+    #[test]
+    fn type_and_delete_degree_sign() {
+        let mut calc = Calculator::new();
+        for c in "90°+1".chars() {
+            calc.append_key_to_calc(&CalcKey::Key(c));
+        }
+        calc.move_cursor_left();
+        calc.move_cursor_left();
+        calc.append_key_to_calc(&CalcKey::Delete);
+        calc.append_key_to_calc(&CalcKey::Key('*'));
+        assert_eq!("90*+1", calc.calc);
+        calc.move_cursor_end();
+        assert_eq!(5, calc.calc_pos);
+        assert_eq!(vec!["90","°"], Calculator::parse_calc_to_tokens("90°").unwrap());
     }
 
     #[test]
     fn is_within_acceptable_range_one_billionth() {
-        assert_eq!(true, Calculator::is_within_acceptable_range(1.0, 1.000000001));
+        assert!(Calculator::is_within_acceptable_range(1.0, 1.000000001));
     }
 
     #[test]
     fn is_within_acceptable_range_two_billionths() {
-        assert_eq!(true, Calculator::is_within_acceptable_range(1.000000002, 1.000000001));
+        assert!(Calculator::is_within_acceptable_range(1.000000002, 1.000000001));
     }
 
     #[test]
     fn is_within_acceptable_range_two_millionths() {
-        assert_eq!(true, Calculator::is_within_acceptable_range(1.000002, 1.000001));
+        assert!(Calculator::is_within_acceptable_range(1.000002, 1.000001));
     }
 
     #[test]
     fn is_within_acceptable_range_four_nines() {
-        assert_eq!(false, Calculator::is_within_acceptable_range(1.0, 0.9999));
+        assert!(!Calculator::is_within_acceptable_range(1.0, 0.9999));
     }
 
     #[test]
     fn is_within_acceptable_range_five_nines() {
-        assert_eq!(true, Calculator::is_within_acceptable_range(1.0, 0.99999));
+        assert!(Calculator::is_within_acceptable_range(1.0, 0.99999));
     }
 
     #[test]
     fn is_within_acceptable_range_two_zeros() {
-        assert_eq!(false, Calculator::is_within_acceptable_range(0.0, 0.001));
+        assert!(!Calculator::is_within_acceptable_range(0.0, 0.001));
     }
 
     #[test]
     fn is_within_acceptable_range_three_zeros() {
-        assert_eq!(false, Calculator::is_within_acceptable_range(0.0, 0.0001));
+        assert!(!Calculator::is_within_acceptable_range(0.0, 0.0001));
     }
 
     #[test]
     fn is_within_acceptable_range_four_zeros() {
-        assert_eq!(false, Calculator::is_within_acceptable_range(0.0, 0.00001));
+        assert!(!Calculator::is_within_acceptable_range(0.0, 0.00001));
     }
 
     #[test]
     fn is_within_acceptable_range_five_zeros() {
-        assert_eq!(false, Calculator::is_within_acceptable_range(0.0, 0.000001));
+        assert!(!Calculator::is_within_acceptable_range(0.0, 0.000001));
     }
 
     #[test]
     fn is_within_acceptable_range_six_zeros() {
-        assert_eq!(false, Calculator::is_within_acceptable_range(0.0, 0.0000001));
+        assert!(!Calculator::is_within_acceptable_range(0.0, 0.0000001));
     }
 
     #[test]
     fn is_within_acceptable_range_hundred_thousandth() {
-        assert_eq!(true, Calculator::is_within_acceptable_range(0.0000099999, 0.00001));
+        assert!(Calculator::is_within_acceptable_range(0.0000099999, 0.00001));
     }
 
     #[test]
     fn is_within_acceptable_range_tenth_hundredth() {
-        assert_eq!(false, Calculator::is_within_acceptable_range(0.01, 0.1));
+        assert!(!Calculator::is_within_acceptable_range(0.01, 0.1));
     }
 
     #[test]
     fn is_within_acceptable_range_six_zeros_half() {
-        assert_eq!(false, Calculator::is_within_acceptable_range(0.0000002, 0.0000001));
+        assert!(!Calculator::is_within_acceptable_range(0.0000002, 0.0000001));
     }
 
     #[test]
     fn is_within_acceptable_range_five_zeros_double() {
-        assert_eq!(false, Calculator::is_within_acceptable_range(0.000001, 0.000002));
+        assert!(!Calculator::is_within_acceptable_range(0.000001, 0.000002));
     }
 
     #[test]
     fn is_within_acceptable_range_zero_and_tiny() {
-        assert_eq!(false, Calculator::is_within_acceptable_range(0.0, 0.000005));
+        assert!(!Calculator::is_within_acceptable_range(0.0, 0.000005));
     }
 
     #[test]
     fn is_within_acceptable_range_tiny_and_zero() {
-        assert_eq!(false, Calculator::is_within_acceptable_range(0.000005, 0.0));
+        assert!(!Calculator::is_within_acceptable_range(0.000005, 0.0));
+    }
+
+    #[test]
+    fn is_within_acceptable_range_near_zero() {
+        assert!(Calculator::is_within_acceptable_range(1.2246467991473532e-16, 1e-60));
+        assert!(!Calculator::is_within_acceptable_range(1e-9, 1e-60));
     }
 
     #[test]
     fn is_within_acceptable_range_zeroes() {
-        assert_eq!(true, Calculator::is_within_acceptable_range(0.0, 0.0));
+        assert!(Calculator::is_within_acceptable_range(0.0, 0.0));
     }
 
     #[test]
     fn perform_calc_js_eval_int() {
-        assert_eq!(CalcResult::Float(0.0), Calculator::perform_calc_js_eval("1 - 1"));
+        assert_eq!(CalcResult::Float(0.0), Calculator::new().perform_calc_js_eval("1 - 1"));
     }
 
     #[test]
     fn perform_calc_js_eval_two_digit_int() {
-        assert_eq!(CalcResult::Float(10.0), Calculator::perform_calc_js_eval("11 - 1"));
+        assert_eq!(CalcResult::Float(10.0), Calculator::new().perform_calc_js_eval("11 - 1"));
     }
 
     #[test]
     fn perform_calc_js_eval_negative_int() {
-        assert_eq!(CalcResult::Float(-2.0), Calculator::perform_calc_js_eval("-1 - 1"));
+        assert_eq!(CalcResult::Float(-2.0), Calculator::new().perform_calc_js_eval("-1 - 1"));
     }
 
     #[test]
     fn perform_calc_js_eval_negative_int_last() {
-        assert_eq!(CalcResult::Float(11.0), Calculator::perform_calc_js_eval("10 - -1"));
+        assert_eq!(CalcResult::Float(11.0), Calculator::new().perform_calc_js_eval("10 - -1"));
     }
 
     #[test]
     fn perform_calc_js_eval_decimal() {
-        assert_eq!(CalcResult::Float(4.0), Calculator::perform_calc_js_eval("5.0 - 1"));
+        assert_eq!(CalcResult::Float(4.0), Calculator::new().perform_calc_js_eval("5.0 - 1"));
     }
 
     #[test]
     fn perform_calc_js_eval_decimal_last() {
-        assert_eq!(CalcResult::Float(4.0), Calculator::perform_calc_js_eval("5 - 1.0"));
+        assert_eq!(CalcResult::Float(4.0), Calculator::new().perform_calc_js_eval("5 - 1.0"));
     }
 
     #[test]
     fn perform_calc_js_eval_decimal_no_zero_last() {
-        assert_eq!(CalcResult::Float(4.9), Calculator::perform_calc_js_eval("5 - .1"));
+        assert_eq!(CalcResult::Float(4.9), Calculator::new().perform_calc_js_eval("5 - .1"));
     }
 
     #[test]
     fn perform_calc_js_eval_two_digit_decimal_no_zero_last() {
-        assert_eq!(CalcResult::Float(4.89), Calculator::perform_calc_js_eval("5 - .11"));
+        assert_eq!(CalcResult::Float(4.89), Calculator::new().perform_calc_js_eval("5 - .11"));
+    }
+
+    #[test]
+    fn perform_calc_js_eval_power_power() {
+        assert_eq!(CalcResult::Float(512.0), Calculator::new().perform_calc_js_eval("2^3^2"));
+    }
+
+    #[test]
+    fn perform_calc_js_eval_negative_power() {
+        assert_eq!(CalcResult::Float(-4.0), Calculator::new().perform_calc_js_eval("-2^2"));
+    }
+
+    #[test]
+    fn perform_calc_js_eval_unary_minus_and_plus() {
+        assert_eq!(CalcResult::Float(-7.0), Calculator::new().perform_calc_js_eval("-(3+4)"));
+        assert_eq!(CalcResult::Float(5.0), Calculator::new().perform_calc_js_eval("--5"));
+        assert_eq!(CalcResult::Float(6.0), Calculator::new().perform_calc_js_eval("-+-(+7-1)"));
+    }
+
+    #[test]
+    fn perform_calc_js_eval_function() {
+        assert_eq!(CalcResult::Float(4.0), Calculator::new().perform_calc_js_eval("sqrt(4)^2"));
+    }
+
+    #[test]
+    fn perform_calc_js_eval_function_with_digits() {
+        assert_eq!(CalcResult::Float(2.0), Calculator::new().perform_calc_js_eval("log10(100)"));
+    }
+
+    #[test]
+    fn perform_calc_js_eval_function_arguments() {
+        assert_eq!(CalcResult::Float(3.0), Calculator::new().perform_calc_js_eval("max(1, 2, 3)"));
+    }
+
+    #[test]
+    fn perform_calc_js_eval_round_digits() {
+        assert_eq!(CalcResult::Float(1.23), Calculator::new().perform_calc_js_eval("round(1.23456, 2)"));
+    }
+
+    #[test]
+    fn perform_calc_js_eval_round() {
+        assert_eq!(CalcResult::Float(3.0), Calculator::new().perform_calc_js_eval("round(2.5)"));
+    }
+
+    #[test]
+    fn perform_calc_js_eval_variable() {
+        let mut calc = Calculator::new();
+        calc.variables.insert("x".to_string(), BigDecimal::from_str("2.5").unwrap());
+        assert_eq!(CalcResult::Float(5.0), calc.perform_calc_js_eval("x*2"));
+    }
+
+    #[test]
+    fn perform_calc_js_eval_implicit_multiplication() {
+        assert_eq!(CalcResult::Float(1.5), Calculator::new().perform_calc_js_eval("1/2(3)"));
+    }
+
+    #[test]
+    fn perform_calc_js_eval_scientific_notation() {
+        assert_eq!(CalcResult::Float(0.003), Calculator::new().perform_calc_js_eval("1.5e-3*2"));
+        assert_eq!(CalcResult::Float(2000.5), Calculator::new().perform_calc_js_eval("2e3+.5"));
+    }
+
+    #[test]
+    fn perform_calc_js_eval_scientific_notation_exponent_too_large() {
+        assert!(matches!(Calculator::new().perform_calc_js_eval("1e99999999"), CalcResult::Error(_)));
+    }
+
+    #[test]
+    fn perform_calc_js_eval_base_literals() {
+        assert_eq!(CalcResult::Float(266.0), Calculator::new().perform_calc_js_eval("0xFF + 0b1011"));
+    }
+
+    #[test]
+    fn perform_calc_js_eval_constant() {
+        assert_eq!(CalcResult::Float(std::f64::consts::PI * 2.0), Calculator::new().perform_calc_js_eval("pi*2"));
+    }
+
+    #[test]
+    fn perform_calc_js_eval_thousands_separator() {
+        assert_eq!(CalcResult::Float(2000.0), Calculator::new().perform_calc_js_eval("$1,000 * 2"));
+    }
+
+    #[test]
+    fn is_evalexpr_comparable_basic() {
+        assert!(Calculator::new().is_evalexpr_comparable("(1 + 2) * 3 / 4 ^ 5"));
+    }
+
+    #[test]
+    fn is_evalexpr_comparable_remainder() {
+        assert!(!Calculator::new().is_evalexpr_comparable("7 % 3"));
+    }
+
+    #[test]
+    fn is_evalexpr_comparable_floor_divide() {
+        assert!(!Calculator::new().is_evalexpr_comparable("7 // 3"));
+    }
+
+    #[test]
+    fn is_evalexpr_comparable_percent() {
+        assert!(!Calculator::new().is_evalexpr_comparable("200 + 15%"));
+    }
+
+    #[test]
+    fn is_evalexpr_comparable_history_reference() {
+        assert!(!Calculator::new().is_evalexpr_comparable("#1*2"));
+        assert!(Calculator::new().is_evalexpr_comparable("ans*2"));
+    }
+
+    #[test]
+    fn is_evalexpr_comparable_user_function() {
+        let mut calc = Calculator::new();
+        calc.calc = String::from("f(x) = x + 1");
+        calc.perform_calculation();
+        assert!(!calc.is_evalexpr_comparable("f(2)"));
+    }
+
+    #[test]
+    fn is_evalexpr_comparable_bitwise() {
+        assert!(!Calculator::new().is_evalexpr_comparable("6 & 3"));
+        assert!(!Calculator::new().is_evalexpr_comparable("6 xor 3"));
+        assert!(!Calculator::new().is_evalexpr_comparable("~6"));
+        assert!(!Calculator::new().is_evalexpr_comparable("1 << 3"));
+    }
+
+    #[test]
+    fn group_exponents_for_evalexpr_power_power() {
+        assert_eq!("(2)^((3)^(2))", Calculator::group_exponents_for_evalexpr("2^3^2"));
+    }
+
+    #[test]
+    fn group_exponents_for_evalexpr_function() {
+        assert_eq!("2*(sqrt(4))^(2)", Calculator::group_exponents_for_evalexpr("2*sqrt(4)^2"));
+    }
+
+    #[test]
+    fn group_exponents_for_evalexpr_paren_and_multiply() {
+        assert_eq!("(2)^((1+1))*3", Calculator::group_exponents_for_evalexpr("2^(1+1)*3"));
     }
 
     #[test]
@@ -1654,12 +6417,32 @@ mod tests {
         assert_eq!("5.0 - .11", Calculator::convert_integers_to_decimals("5 - .11"));
     }
 
+    #[test]
+    fn convert_integers_to_decimals_identifier_with_digits() {
+        assert_eq!("log10(100.0)", Calculator::convert_integers_to_decimals("log10(100)"));
+    }
+
     #[test]
     fn convert_integers_to_decimals_decimal() {
         assert_eq!("5.0-1.23", Calculator::convert_integers_to_decimals("5.0-1.23"));
     }
 
+    #[test]
+    fn convert_integers_to_decimals_after_decimal() {
+        assert_eq!("1.5+5.0/3.0", Calculator::convert_integers_to_decimals("1.5+5/3"));
+    }
+
+    #[test]
+    fn convert_integers_to_decimals_scientific_notation() {
+        assert_eq!("2.0e3+1.5e-3*2.0E+2", Calculator::convert_integers_to_decimals("2e3+1.5e-3*2E+2"));
+    }
+
+    #[test]
+    fn convert_integers_to_decimals_constant_e() {
+        assert_eq!("2.0*e-3.0", Calculator::convert_integers_to_decimals("2*e-3"));
+    }
+
     // add tets for invalid inputs for get_token_matching_str()
 
     // if needed, add tests for whitespace-removed calcs with negative numbers, like "1 - -.1" and "5 * -0.1"
-}
\ No newline at end of file
+}